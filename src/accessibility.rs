@@ -0,0 +1,129 @@
+use manycore_parser::{Directions, ElementIDT, ManycoreSystem, WithID};
+use serde::Serialize;
+
+use crate::{direction_str, SVG};
+
+static ROOT_ROLE: &'static str = "img";
+static GROUP_ROLE: &'static str = "group";
+static TITLE_ID: &'static str = "svgTitle";
+static DESC_ID: &'static str = "svgDesc";
+static LABELLED_BY: &'static str = "svgTitle svgDesc";
+
+/// Object representation of the root SVG `<title>` and `<desc>`.
+#[derive(Serialize, Debug)]
+pub(crate) struct AccessibleText {
+    #[serde(rename = "@id")]
+    id: &'static str,
+    #[serde(rename = "$text")]
+    text: String,
+}
+
+/// Role and label that let assistive technologies announce an SVG `<g>` as a single group.
+#[derive(Serialize, Debug)]
+pub(crate) struct GroupLabel {
+    #[serde(rename = "@role")]
+    role: &'static str,
+    #[serde(rename = "@aria-label")]
+    aria_label: &'static str,
+}
+
+impl GroupLabel {
+    fn new(aria_label: &'static str) -> Self {
+        Self {
+            role: GROUP_ROLE,
+            aria_label,
+        }
+    }
+}
+
+/// The most loaded channel in a system.
+struct HottestLink<'a> {
+    core_id: &'a ElementIDT,
+    direction: &'a Directions,
+    load: u16,
+    bandwidth: u16,
+}
+
+/// Finds the channel with the highest load to bandwidth ratio, if any channel carries load.
+/// Channels with no bandwidth and some load are overloaded, so they are the hottest.
+fn hottest_link(manycore: &ManycoreSystem) -> Option<HottestLink> {
+    let mut hottest = None;
+    let mut hottest_ratio = 0.0;
+
+    for core in manycore.cores().list() {
+        for (direction, channel) in core.channels().channel() {
+            let load = *channel.current_load();
+            let bandwidth = *channel.bandwidth();
+
+            let ratio = if bandwidth > 0 {
+                f32::from(load) / f32::from(bandwidth)
+            } else if load > 0 {
+                f32::INFINITY
+            } else {
+                0.0
+            };
+
+            if ratio > hottest_ratio {
+                hottest_ratio = ratio;
+                hottest = Some(HottestLink {
+                    core_id: core.id(),
+                    direction,
+                    load,
+                    bandwidth,
+                });
+            }
+        }
+    }
+
+    hottest
+}
+
+impl SVG {
+    /// Adds a textual summary of the system and ARIA roles and labels to the [`SVG`].
+    pub(crate) fn add_accessibility(&mut self, manycore: &ManycoreSystem) {
+        let rows = manycore.rows();
+        let columns = manycore.columns();
+
+        let mut summary = format!(
+            "{} by {} manycore system with {} cores and {} tasks.",
+            rows,
+            columns,
+            manycore.cores().list().len(),
+            manycore.task_graph().tasks().len()
+        );
+        match hottest_link(manycore) {
+            Some(link) => summary.push_str(
+                format!(
+                    " The hottest link is the {} channel of Core {}, with a load of {}/{}.",
+                    direction_str(link.direction),
+                    link.core_id,
+                    link.load,
+                    link.bandwidth
+                )
+                .as_str(),
+            ),
+            None => summary.push_str(" No link carries any load."),
+        }
+
+        self.role = Some(ROOT_ROLE);
+        self.aria_labelledby = Some(LABELLED_BY);
+        self.title = Some(AccessibleText {
+            id: TITLE_ID,
+            text: format!("{}x{} manycore system", rows, columns),
+        });
+        self.desc = Some(AccessibleText {
+            id: DESC_ID,
+            text: summary,
+        });
+
+        self.root
+            .processing_group
+            .set_label(Some(GroupLabel::new("Cores and routers")));
+        self.root
+            .information_group
+            .set_label(Some(GroupLabel::new("Core, router and link information")));
+        self.root
+            .sinks_sources_group
+            .set_label(Some(GroupLabel::new("Border routers")));
+    }
+}
@@ -0,0 +1,243 @@
+use manycore_parser::{ManycoreSystem, WithID};
+use serde::Serialize;
+
+use crate::{
+    check_same_structure,
+    style::ElementRule,
+    tasks_group::{missing_task, TasksGroup},
+    BaseConfiguration, Configuration, InformationGroup, Offsets, SVGError, SVGErrorKind, SVG,
+};
+
+static ANIMATION_ID: &'static str = "animation";
+static ANIMATION_CLOCK_ID: &'static str = "animationClock";
+
+/// Object representation of an SMIL `<set>` element.
+/// When `href` is provided, the target is the referenced element rather than the parent.
+#[derive(Serialize)]
+struct AnimationSet {
+    #[serde(rename = "@id", skip_serializing_if = "Option::is_none")]
+    id: Option<&'static str>,
+    #[serde(rename = "@href", skip_serializing_if = "Option::is_none")]
+    href: Option<String>,
+    #[serde(rename = "@attributeName")]
+    attribute_name: String,
+    #[serde(rename = "@to")]
+    to: String,
+    #[serde(rename = "@begin")]
+    begin: String,
+    #[serde(rename = "@dur")]
+    dur: String,
+}
+
+impl AnimationSet {
+    /// Generates a new [`AnimationSet`] active for the frame starting at `offset` milliseconds.
+    fn frame(
+        href: Option<String>,
+        attribute_name: String,
+        to: String,
+        offset: u64,
+        frame_duration: u32,
+    ) -> Self {
+        Self {
+            id: None,
+            href,
+            attribute_name,
+            to,
+            begin: format!("{}.begin+{}ms", ANIMATION_CLOCK_ID, offset),
+            dur: format!("{}ms", frame_duration),
+        }
+    }
+}
+
+/// Object representation of the SVG `<g>` of a single animation frame.
+/// The frame is hidden outside its time slot. Element specific rules of the frame
+/// are turned into `<set>` elements targeting said elements.
+#[derive(Serialize)]
+struct AnimationFrame {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "@visibility")]
+    visibility: &'static str,
+    #[serde(rename = "set")]
+    sets: Vec<AnimationSet>,
+    #[serde(rename = "g")]
+    information_group: InformationGroup,
+    #[serde(rename = "g")]
+    tasks_group: TasksGroup,
+}
+
+/// Object representation of the animation SVG `<g>`.
+/// Contains a looping clock and every [`AnimationFrame`].
+#[derive(Serialize)]
+#[serde(rename = "g")]
+pub(crate) struct Animation {
+    #[serde(rename = "@id")]
+    id: &'static str,
+    #[serde(rename = "set")]
+    clock: AnimationSet,
+    #[serde(rename = "g")]
+    frames: Vec<AnimationFrame>,
+}
+
+/// Turns the provided [`ElementRule`]s, e.g. `#c0 {fill: #f97316;}`, into [`AnimationSet`]s
+/// for the frame starting at `offset` milliseconds.
+fn rules_to_sets(rules: &[ElementRule], offset: u64, frame_duration: u32) -> Vec<AnimationSet> {
+    rules
+        .iter()
+        .flat_map(|rule| {
+            rule.declarations().iter().map(move |(property, value)| {
+                AnimationSet::frame(
+                    Some(format!("#{}", rule.id())),
+                    property.to_string(),
+                    value.clone(),
+                    offset,
+                    frame_duration,
+                )
+            })
+        })
+        .collect()
+}
+
+impl SVG {
+    /// Generates the [`TasksGroup`] of the tasks allocated in `manycore`, against the [`SVG`] geometry.
+    fn frame_tasks(&self, manycore: &ManycoreSystem) -> Result<TasksGroup, SVGError> {
+        let mut tasks_group = TasksGroup::new();
+
+        for (core, processing_group) in manycore
+            .cores()
+            .list()
+            .iter()
+            .zip(self.root.processing_group.g().iter())
+        {
+            if let Some(task_id) = core.allocated_task() {
+                let allocated_task = manycore
+                    .task_graph()
+                    .tasks()
+                    .get(task_id)
+                    .ok_or_else(|| missing_task(core.id(), task_id))?;
+                let (r, c) = processing_group.coordinates();
+
+                tasks_group.add_task(
+                    r,
+                    c,
+                    core.id(),
+                    allocated_task,
+                    &self.top_left,
+                    &self.processed_base_configuration,
+                )?;
+            }
+        }
+
+        Ok(tasks_group)
+    }
+
+    /// Generates a self-playing, looping [`SVG`] from a time series of [`ManycoreSystem`]s.
+    /// Systems must share their structure, static geometry is generated once, from the first system.
+    /// Each system then becomes a frame, displayed for `frame_duration` milliseconds, with its own
+    /// fills, loads, labels and tasks according to the provided [`Configuration`]. The viewBox fits every frame.
+    pub fn try_animate(
+        manycores: &mut [ManycoreSystem],
+        configuration: &Configuration,
+        base_configuration: &BaseConfiguration,
+        frame_duration: u32,
+    ) -> Result<SVG, SVGError> {
+        let first = manycores.first().ok_or_else(|| {
+            SVGError::new(SVGErrorKind::GenerationError(String::from(
+                "At least one system is required to generate an animation.",
+            )))
+        })?;
+        for manycore in manycores.iter().skip(1) {
+            check_same_structure(first, manycore)?;
+        }
+
+        // Geometry is shared by every frame.
+        let mut svg = SVG::try_from_manycore_with_base_config(first, base_configuration)?;
+        let number_of_cores = first.cores().list().len();
+
+        let mut frames = Vec::with_capacity(manycores.len());
+        let mut offsets: Option<Offsets> = None;
+
+        for (i, manycore) in manycores.iter_mut().enumerate() {
+            // Tasks depend on the system allocation, so each frame starts from its own.
+            svg.root.tasks_group = svg.frame_tasks(manycore)?;
+            if *svg.base_configuration.tooltips() {
+                svg.add_task_tooltips(manycore)?;
+            }
+            // Generating an update consumes some of the configuration fields, so each frame needs its own.
+            svg.apply_configuration(manycore, &mut configuration.clone())?;
+
+            let view_box = svg.view_box();
+            let frame_offsets = Offsets::new(
+                view_box.x,
+                view_box.y,
+                view_box.x.saturating_add(view_box.width),
+                view_box.y.saturating_add(view_box.height),
+            );
+            match offsets.as_mut() {
+                Some(offsets) => offsets.update(frame_offsets),
+                None => offsets = Some(frame_offsets),
+            }
+
+            let offset = u64::try_from(i)?.saturating_mul(u64::from(frame_duration));
+            let id = format!("frame{}", i);
+
+            let mut sets = vec![AnimationSet::frame(
+                None,
+                String::from("visibility"),
+                String::from("visible"),
+                offset,
+                frame_duration,
+            )];
+            sets.extend(rules_to_sets(
+                svg.style.element_rules(),
+                offset,
+                frame_duration,
+            ));
+
+            // Rules shared by every frame (font, theme, border routers) stay in the stylesheet.
+            svg.style.element_rules_mut().clear();
+
+            // The frame takes its layers, the SVG is left without information and tasks.
+            let mut information_group = std::mem::replace(
+                &mut svg.root.information_group,
+                InformationGroup::new(&number_of_cores),
+            );
+            information_group.set_id(format!("{}Information", id));
+            let mut tasks_group = std::mem::replace(&mut svg.root.tasks_group, TasksGroup::new());
+            tasks_group.set_id(format!("{}Tasks", id));
+
+            frames.push(AnimationFrame {
+                id,
+                visibility: "hidden",
+                sets,
+                information_group,
+                tasks_group,
+            });
+        }
+
+        let total_duration = u64::try_from(frames.len())?.saturating_mul(u64::from(frame_duration));
+
+        svg.view_box.restore_from(&svg.base_view_box);
+        if let Some(offsets) = offsets {
+            svg.view_box.fit_offsets(&offsets);
+        }
+        svg.root.animation = Some(Animation {
+            id: ANIMATION_ID,
+            // Restarts whenever it ends, every frame begins relative to it.
+            clock: AnimationSet {
+                id: Some(ANIMATION_CLOCK_ID),
+                href: None,
+                attribute_name: String::from("visibility"),
+                to: String::from("visible"),
+                begin: format!("0ms;{}.end", ANIMATION_CLOCK_ID),
+                dur: format!("{}ms", total_duration),
+            },
+            frames,
+        });
+
+        // Every frame's text must be covered by the font, not only the last one's.
+        svg.apply_font_embedding()?;
+
+        Ok(svg)
+    }
+}
@@ -2,7 +2,7 @@ use const_format::concatcp;
 use manycore_parser::ElementIDT;
 use serde::Serialize;
 
-use crate::{CoordinateT, ROUTER_OFFSET, SIDE_LENGTH};
+use crate::{CoordinateT, GeometryConfiguration};
 
 pub(crate) const FREEFORM_CLIP_PATH_ID: &'static str = "crop";
 
@@ -13,6 +13,50 @@ pub(crate) static USE_FREEFORM_CLIP_PATH: &'static str = concatcp!("url(#", FREE
 struct Polygon {
     #[serde(rename = "@points")]
     points: String,
+    /// The polygon vertices, if known. Used to measure the room available inside it.
+    #[serde(skip)]
+    vertices: Vec<(CoordinateT, CoordinateT)>,
+}
+
+impl Polygon {
+    /// Generates a new [`Polygon`] from its vertices.
+    fn from_vertices(vertices: Vec<(CoordinateT, CoordinateT)>) -> Self {
+        Self {
+            points: vertices
+                .iter()
+                .map(|(x, y)| format!("{x} {y}"))
+                .collect::<Vec<String>>()
+                .join(", "),
+            vertices,
+        }
+    }
+
+    /// Calculates the leftmost and rightmost points of the polygon on the horizontal line at `y`.
+    fn span_at(&self, y: CoordinateT) -> Option<(CoordinateT, CoordinateT)> {
+        let mut span: Option<(CoordinateT, CoordinateT)> = None;
+
+        for (i, &(x1, y1)) in self.vertices.iter().enumerate() {
+            let (x2, y2) = self.vertices[(i + 1) % self.vertices.len()];
+
+            if y < y1.min(y2) || y > y1.max(y2) {
+                continue;
+            }
+
+            let xs = if y1 == y2 {
+                (x1.min(x2), x1.max(x2))
+            } else {
+                let x = x1 + (y - y1) * (x2 - x1) / (y2 - y1);
+                (x, x)
+            };
+
+            span = Some(match span {
+                Some((left, right)) => (left.min(xs.0), right.max(xs.1)),
+                None => xs,
+            });
+        }
+
+        span
+    }
 }
 
 /// Object representation of an SVG `<clipPath>`.
@@ -30,6 +74,7 @@ impl ClipPath {
             id: FREEFORM_CLIP_PATH_ID.to_string(),
             polygon: Polygon {
                 points: polygon_points,
+                vertices: Vec::new(),
             },
         }
     }
@@ -45,36 +90,60 @@ impl ClipPath {
     }
 
     /// Creates a new [`ClipPath`] instance from a core's position and id. Used to clip information layer.
-    pub(crate) fn for_core(id: ElementIDT, x: CoordinateT, y: CoordinateT) -> Self {
-        let full_y = y.saturating_add(SIDE_LENGTH);
-        let full_x = x.saturating_add(SIDE_LENGTH);
+    pub(crate) fn for_core(
+        id: ElementIDT,
+        x: CoordinateT,
+        y: CoordinateT,
+        geometry: &GeometryConfiguration,
+    ) -> Self {
+        let full_y = y.saturating_add(*geometry.side_length());
+        let full_x = x.saturating_add(*geometry.side_length());
 
         Self {
             id: ClipPath::make_core_id(&id),
-            polygon: Polygon {
-                points: format!(
-                    "{x} {y}, {x} {full_y}, {full_x} {full_y}, {full_x} {}, {} {y}",
-                    full_y.saturating_sub(ROUTER_OFFSET),
-                    x.saturating_add(ROUTER_OFFSET)
-                ),
-            },
+            polygon: Polygon::from_vertices(vec![
+                (x, y),
+                (x, full_y),
+                (full_x, full_y),
+                (full_x, full_y.saturating_sub(*geometry.router_offset())),
+                (x.saturating_add(*geometry.router_offset()), y),
+            ]),
         }
     }
 
     /// Creates a new [`ClipPath`] instance from a router's position and id. Used to clip information layer.
-    pub(crate) fn for_router(id: ElementIDT, x: CoordinateT, y: CoordinateT) -> Self {
-        let min_y = y.saturating_sub(ROUTER_OFFSET);
-        let full_x = x.saturating_add(SIDE_LENGTH);
-        let full_y = min_y.saturating_add(SIDE_LENGTH);
+    pub(crate) fn for_router(
+        id: ElementIDT,
+        x: CoordinateT,
+        y: CoordinateT,
+        geometry: &GeometryConfiguration,
+    ) -> Self {
+        let min_y = y.saturating_sub(*geometry.router_offset());
+        let full_x = x.saturating_add(*geometry.side_length());
+        let full_y = min_y.saturating_add(*geometry.side_length());
 
         Self {
             id: ClipPath::make_router_id(&id),
-            polygon: Polygon {
-                points: format!(
-                    "{x} {y}, {x} {min_y}, {full_x} {min_y}, {full_x} {full_y}, {} {full_y}",
-                    full_x.saturating_sub(ROUTER_OFFSET),
-                ),
-            },
+            polygon: Polygon::from_vertices(vec![
+                (x, y),
+                (x, min_y),
+                (full_x, min_y),
+                (full_x, full_y),
+                (full_x.saturating_sub(*geometry.router_offset()), full_y),
+            ]),
         }
     }
+
+    /// Calculates the horizontal room inside the clip path shared by every line between `top` and `bottom`.
+    /// Clip paths are convex, so the narrowest span is at either end.
+    pub(crate) fn horizontal_span(
+        &self,
+        top: CoordinateT,
+        bottom: CoordinateT,
+    ) -> Option<(CoordinateT, CoordinateT)> {
+        let (top_left, top_right) = self.polygon.span_at(top)?;
+        let (bottom_left, bottom_right) = self.polygon.span_at(bottom)?;
+
+        Some((top_left.max(bottom_left), top_right.min(bottom_right)))
+    }
 }
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use getset::Getters;
+use manycore_parser::ManycoreSystem;
+use serde::Serialize;
+
+use crate::{
+    CoordinateT, Offsets, ProcessedBaseConfiguration, ProcessingParentGroup, TextInformation,
+};
+
+static COMMUNICATION_COLOUR: &'static str = "#7c3aed";
+static MINIMUM_COMMUNICATION_STROKE_WIDTH: f64 = 2.0;
+static MAXIMUM_COMMUNICATION_STROKE_WIDTH: f64 = 6.0;
+// How far the curve bends away from the straight line, as a fraction of the line length.
+static CURVE_FACTOR: f64 = 0.25;
+static ARROW_LENGTH: f64 = 12.0;
+static ARROW_HALF_WIDTH: f64 = 5.0;
+
+/// Object representation of an SVG `<path>` used in a [`CommunicationArrow`].
+#[derive(Serialize)]
+struct CommunicationPath {
+    #[serde(rename = "@d")]
+    d: String,
+    #[serde(rename = "@fill")]
+    fill: &'static str,
+    #[serde(rename = "@stroke")]
+    stroke: &'static str,
+    #[serde(rename = "@stroke-width")]
+    stroke_width: String,
+}
+
+/// Object representation of the SVG `<g>` for a single task graph edge.
+/// A curved line from the sender's core to the receiver's core, an arrow head and the communication cost.
+#[derive(Serialize)]
+struct CommunicationArrow {
+    #[serde(rename = "path")]
+    line: CommunicationPath,
+    #[serde(rename = "path")]
+    head: CommunicationPath,
+    #[serde(rename = "text")]
+    label: TextInformation,
+}
+
+/// A point in the SVG coordinate space. Used to compute the curves.
+#[derive(Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Point {
+    fn new(x: CoordinateT, y: CoordinateT) -> Self {
+        Self {
+            x: f64::from(x),
+            y: f64::from(y),
+        }
+    }
+
+    fn offset(self, direction: Point, distance: f64) -> Self {
+        Self {
+            x: self.x + direction.x * distance,
+            y: self.y + direction.y * distance,
+        }
+    }
+
+    fn rounded(self) -> (CoordinateT, CoordinateT) {
+        (self.x.round() as CoordinateT, self.y.round() as CoordinateT)
+    }
+}
+
+impl CommunicationArrow {
+    /// Generates a new [`CommunicationArrow`] between the provided core centres.
+    fn new(
+        from: Point,
+        to: Point,
+        cost: u16,
+        stroke_width: f64,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> (Self, Offsets) {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let length = dx.hypot(dy);
+        // Left-hand normal, so that A -> B and B -> A curve on opposite sides.
+        let normal = Point {
+            x: dy / length,
+            y: -dx / length,
+        };
+
+        let middle = Point {
+            x: (from.x + to.x) / 2.0,
+            y: (from.y + to.y) / 2.0,
+        };
+        let control = middle.offset(normal, length * CURVE_FACTOR);
+
+        // Arrow head points along the curve tangent at its end.
+        let (tx, ty) = (to.x - control.x, to.y - control.y);
+        let tangent_length = tx.hypot(ty);
+        let tangent = Point {
+            x: tx / tangent_length,
+            y: ty / tangent_length,
+        };
+        let tangent_normal = Point {
+            x: -tangent.y,
+            y: tangent.x,
+        };
+        let base = to.offset(tangent, -ARROW_LENGTH);
+
+        let (from_x, from_y) = from.rounded();
+        let (control_x, control_y) = control.rounded();
+        let (base_x, base_y) = base.rounded();
+        let (tip_x, tip_y) = to.rounded();
+        let (left_x, left_y) = base.offset(tangent_normal, ARROW_HALF_WIDTH).rounded();
+        let (right_x, right_y) = base.offset(tangent_normal, -ARROW_HALF_WIDTH).rounded();
+
+        // The curve middle point sits halfway between the straight line and the control point.
+        let (label_x, label_y) = middle.offset(normal, length * CURVE_FACTOR / 2.0).rounded();
+        let label = TextInformation::new(
+            label_x,
+            label_y,
+            *processed_base_configuration.attribute_font_size(),
+            "middle",
+            "central",
+            Some(&COMMUNICATION_COLOUR.to_string()),
+            None,
+            cost.to_string(),
+        );
+
+        let half_label_length = label.calculate_length(None).saturating_div(2);
+        let half_font_size = processed_base_configuration
+            .attribute_font_size_coordinate()
+            .saturating_div(2);
+        let offsets = Offsets::new(
+            from_x
+                .min(tip_x)
+                .min(label_x.saturating_sub(half_label_length)),
+            from_y
+                .min(tip_y)
+                .min(label_y.saturating_sub(half_font_size)),
+            from_x
+                .max(tip_x)
+                .max(label_x.saturating_add(half_label_length)),
+            from_y
+                .max(tip_y)
+                .max(label_y.saturating_add(half_font_size)),
+        );
+
+        (
+            Self {
+                line: CommunicationPath {
+                    d: format!("M{from_x},{from_y} Q{control_x},{control_y} {base_x},{base_y}"),
+                    fill: "none",
+                    stroke: COMMUNICATION_COLOUR,
+                    stroke_width: format!("{:.1}", stroke_width),
+                },
+                head: CommunicationPath {
+                    d: format!("M{tip_x},{tip_y} L{left_x},{left_y} L{right_x},{right_y} Z"),
+                    fill: COMMUNICATION_COLOUR,
+                    stroke: "none",
+                    stroke_width: String::from("0"),
+                },
+                label,
+            },
+            offsets,
+        )
+    }
+}
+
+/// Scales a communication cost to a stroke width, from the minimum width at `min_cost`
+/// to the maximum width at `max_cost`. All edges get the minimum width if costs are equal.
+fn stroke_width(cost: u16, min_cost: u16, max_cost: u16) -> f64 {
+    let cost_range = f64::from(max_cost.saturating_sub(min_cost));
+    let weight = if cost_range > 0.0 {
+        f64::from(cost.saturating_sub(min_cost)) / cost_range
+    } else {
+        0.0
+    };
+
+    MINIMUM_COMMUNICATION_STROKE_WIDTH
+        + weight * (MAXIMUM_COMMUNICATION_STROKE_WIDTH - MINIMUM_COMMUNICATION_STROKE_WIDTH)
+}
+
+/// Object representation of the task graph overlay SVG `<g>`.
+/// Draws every task graph edge between the cores its tasks are allocated to.
+/// Line width grows with the edge communication cost.
+#[derive(Serialize, Getters)]
+#[serde(rename = "g")]
+pub(crate) struct CommunicationsOverlay {
+    #[serde(rename = "@id")]
+    id: &'static str,
+    #[serde(rename = "g")]
+    arrows: Vec<CommunicationArrow>,
+    #[serde(skip)]
+    #[getset(get = "pub")]
+    offsets: Offsets,
+}
+
+impl CommunicationsOverlay {
+    /// Generates a new [`CommunicationsOverlay`] for the provided [`ManycoreSystem`].
+    /// Edges between unallocated tasks, or tasks on the same core, are not drawn.
+    /// Returns [`None`] if there is nothing to draw.
+    pub(crate) fn new(
+        manycore: &ManycoreSystem,
+        processing_groups: &ProcessingParentGroup,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> Option<Self> {
+        let half_side_length = *processed_base_configuration.geometry().half_side_length();
+
+        // Task ID -> centre of the core it's allocated to.
+        let mut task_centres: BTreeMap<u16, Point> = BTreeMap::new();
+        for (core, processing_group) in manycore
+            .cores()
+            .list()
+            .iter()
+            .zip(processing_groups.g().iter())
+        {
+            if let Some(task_id) = core.allocated_task() {
+                let (core_x, core_y) = processing_group.core().move_coordinates();
+                task_centres.insert(
+                    *task_id,
+                    Point::new(
+                        core_x.saturating_add(half_side_length),
+                        core_y.saturating_add(half_side_length),
+                    ),
+                );
+            }
+        }
+
+        let drawable: Vec<(Point, Point, u16)> = manycore
+            .task_graph()
+            .edges()
+            .iter()
+            .filter(|edge| edge.from() != edge.to())
+            .filter_map(
+                |edge| match (task_centres.get(edge.from()), task_centres.get(edge.to())) {
+                    (Some(from), Some(to)) => Some((*from, *to, *edge.communication_cost())),
+                    _ => None,
+                },
+            )
+            .filter(|(from, to, _)| from.x != to.x || from.y != to.y)
+            .collect();
+
+        if drawable.is_empty() {
+            return None;
+        }
+
+        let min_cost = drawable.iter().map(|(_, _, cost)| *cost).min().unwrap_or(0);
+        let max_cost = drawable.iter().map(|(_, _, cost)| *cost).max().unwrap_or(0);
+
+        let mut arrows = Vec::with_capacity(drawable.len());
+        let mut offsets: Option<Offsets> = None;
+
+        for (from, to, cost) in drawable {
+            let (arrow, arrow_offsets) = CommunicationArrow::new(
+                from,
+                to,
+                cost,
+                stroke_width(cost, min_cost, max_cost),
+                processed_base_configuration,
+            );
+
+            match offsets.as_mut() {
+                Some(offsets) => offsets.update(arrow_offsets),
+                None => offsets = Some(arrow_offsets),
+            }
+            arrows.push(arrow);
+        }
+
+        Some(Self {
+            id: "taskGraph",
+            arrows,
+            offsets: offsets.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        stroke_width, MAXIMUM_COMMUNICATION_STROKE_WIDTH, MINIMUM_COMMUNICATION_STROKE_WIDTH,
+    };
+
+    #[test]
+    fn can_scale_stroke_width() {
+        assert_eq!(
+            stroke_width(50, 50, 100),
+            MINIMUM_COMMUNICATION_STROKE_WIDTH
+        );
+        assert_eq!(
+            stroke_width(100, 50, 100),
+            MAXIMUM_COMMUNICATION_STROKE_WIDTH
+        );
+        assert_eq!(stroke_width(75, 50, 100), 4.0);
+        assert_eq!(format!("{:.1}", stroke_width(60, 50, 100)), "2.8");
+
+        // Equal costs have nothing to scale against.
+        assert_eq!(stroke_width(30, 30, 30), MINIMUM_COMMUNICATION_STROKE_WIDTH);
+    }
+}
@@ -1,28 +1,36 @@
 use std::{collections::HashMap, fmt::Display};
 
-use getset::Getters;
+use getset::{Getters, Setters};
 use manycore_parser::{Core, Directions, EdgePosition, ElementIDT, WithID};
 use serde::Serialize;
 
 use crate::{
-    sinks_sources_layer::SINKS_SOURCES_CONNECTION_LENGTH, style::EDGE_DATA_CLASS_NAME,
-    CommonAttributes, CoordinateT, Router, TopLeft, HALF_ROUTER_OFFSET, MARKER_HEIGHT,
-    MARKER_REFERENCE, ROUTER_OFFSET, SIDE_LENGTH, USE_FREEFORM_CLIP_PATH,
+    style::EDGE_DATA_CLASS_NAME, CommonAttributes, CoordinateT, DataAttributes, LinkKind, Offsets,
+    ProcessedBaseConfiguration, Router, Tooltip, TopLeft, Topology, CORE_ROUTER_STROKE_WIDTH,
+    DEFAULT_ROUTER_OFFSET, MARKER_HEIGHT, MARKER_REFERENCE, STROKE_CLASS_NAME,
+    USE_FREEFORM_CLIP_PATH,
 };
 
 pub(crate) const EDGE_CONNECTIONS_ID: &'static str = "edgeConnetions";
-static CONNECTION_GAP: CoordinateT = 0i32
-    .saturating_add(HALF_ROUTER_OFFSET)
-    .saturating_mul(3)
-    .saturating_div(4)
-    .wrapping_sub(5);
-
-pub(crate) static CONNECTION_LENGTH: CoordinateT = ROUTER_OFFSET.saturating_mul(4);
+pub(crate) const DEFAULT_CONNECTION_LENGTH: CoordinateT = DEFAULT_ROUTER_OFFSET.saturating_mul(4);
+pub(crate) static MINIMUM_CONNECTION_LENGTH: CoordinateT = 100;
+pub(crate) static MAXIMUM_CONNECTION_LENGTH: CoordinateT = 600;
+// Distance between two adjacent wrap-around lanes.
+static WRAP_LANE_GAP: CoordinateT = MARKER_HEIGHT;
+static CONNECTION_ID_PREFIX: &'static str = "link";
+static SOURCE_ID_PREFIX: &'static str = "source";
+static SINK_ID_PREFIX: &'static str = "sink";
 
 /// Object representation of a connection path.
-#[derive(Serialize, Getters, Debug)]
+#[derive(Serialize, Getters, Setters, Debug)]
 pub(crate) struct Connection {
+    #[serde(rename = "@id")]
+    #[getset(get = "pub")]
+    id: String,
+    #[serde(flatten)]
+    data: DataAttributes,
     #[serde(rename = "@d")]
+    #[getset(get = "pub")]
     d: String,
     #[serde(flatten)]
     attributes: CommonAttributes,
@@ -36,6 +44,9 @@ pub(crate) struct Connection {
     y: CoordinateT,
     #[serde(rename = "@clip-path")]
     clip_path: &'static str,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    tooltip: Option<Tooltip>,
 }
 
 /// Helper struct used when calculating connection paths.
@@ -58,30 +69,38 @@ impl Connection {
         r: &CoordinateT,
         c: &CoordinateT,
         top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> ConnectionPath {
-        let (mut router_x, mut router_y) = Router::get_move_coordinates(r, c, top_left);
+        let geometry = processed_base_configuration.geometry();
+        let side_length = *geometry.side_length();
+        let router_offset = *geometry.router_offset();
+        let half_router_offset = *geometry.half_router_offset();
+        let connection_gap = *geometry.connection_gap();
+        let connection_length = *geometry.connection_length();
+
+        let (mut router_x, mut router_y) = Router::get_move_coordinates(r, c, top_left, geometry);
 
         let path: String;
 
         match direction {
             Directions::North => {
-                router_x = router_x + SIDE_LENGTH - HALF_ROUTER_OFFSET + CONNECTION_GAP;
-                router_y = router_y - ROUTER_OFFSET;
-                path = format!("M{},{} v-{}", router_x, router_y, CONNECTION_LENGTH);
+                router_x = router_x + side_length - half_router_offset + connection_gap;
+                router_y = router_y - router_offset;
+                path = format!("M{},{} v-{}", router_x, router_y, connection_length);
             }
             Directions::East => {
-                router_x = router_x + SIDE_LENGTH;
-                router_y = router_y - HALF_ROUTER_OFFSET - CONNECTION_GAP;
-                path = format!("M{},{} h{}", router_x, router_y, CONNECTION_LENGTH);
+                router_x = router_x + side_length;
+                router_y = router_y - half_router_offset - connection_gap;
+                path = format!("M{},{} h{}", router_x, router_y, connection_length);
             }
             Directions::South => {
-                router_x = router_x + SIDE_LENGTH - HALF_ROUTER_OFFSET - CONNECTION_GAP;
-                router_y = router_y - ROUTER_OFFSET + SIDE_LENGTH;
-                path = format!("M{},{} v{}", router_x, router_y, CONNECTION_LENGTH);
+                router_x = router_x + side_length - half_router_offset - connection_gap;
+                router_y = router_y - router_offset + side_length;
+                path = format!("M{},{} v{}", router_x, router_y, connection_length);
             }
             Directions::West => {
-                router_y = router_y - HALF_ROUTER_OFFSET + CONNECTION_GAP;
-                path = format!("M{},{} h-{}", router_x, router_y, CONNECTION_LENGTH);
+                router_y = router_y - half_router_offset + connection_gap;
+                path = format!("M{},{} h-{}", router_x, router_y, connection_length);
             }
         }
 
@@ -98,27 +117,37 @@ impl Connection {
         r: &CoordinateT,
         c: &CoordinateT,
         top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> EdgePath {
-        let (mut router_x, mut router_y) = Router::get_move_coordinates(r, c, top_left);
+        let geometry = processed_base_configuration.geometry();
+        let side_length = *geometry.side_length();
+        let router_offset = *geometry.router_offset();
+        let half_router_offset = *geometry.half_router_offset();
+        let connection_gap = *geometry.connection_gap();
+        let sinks_sources_connection_length = *processed_base_configuration
+            .sinks_sources_geometry()
+            .connection_length();
+
+        let (mut router_x, mut router_y) = Router::get_move_coordinates(r, c, top_left, geometry);
 
         let (input, output) = match direction {
             Directions::North => {
                 router_x = router_x
-                    .saturating_add(SIDE_LENGTH)
-                    .saturating_sub(HALF_ROUTER_OFFSET);
-                router_y = router_y.saturating_sub(ROUTER_OFFSET);
+                    .saturating_add(side_length)
+                    .saturating_sub(half_router_offset);
+                router_y = router_y.saturating_sub(router_offset);
 
                 let connection_length =
-                    SINKS_SOURCES_CONNECTION_LENGTH.saturating_add(MARKER_HEIGHT);
-                let render_length = SINKS_SOURCES_CONNECTION_LENGTH;
+                    sinks_sources_connection_length.saturating_add(MARKER_HEIGHT);
+                let render_length = sinks_sources_connection_length;
 
                 // Input
-                let input_x = router_x.saturating_sub(CONNECTION_GAP);
+                let input_x = router_x.saturating_sub(connection_gap);
                 let input_y = router_y.saturating_sub(connection_length);
                 let input_s = format!("M{},{} v{}", input_x, input_y, render_length);
 
                 // Output
-                let output_x = router_x.saturating_add(CONNECTION_GAP);
+                let output_x = router_x.saturating_add(connection_gap);
                 let output_s = format!("M{},{} v-{}", output_x, router_y, render_length);
 
                 (
@@ -135,22 +164,22 @@ impl Connection {
                 )
             }
             Directions::East => {
-                router_x = router_x.saturating_add(SIDE_LENGTH);
+                router_x = router_x.saturating_add(side_length);
                 router_y = router_y
-                    .saturating_sub(ROUTER_OFFSET)
-                    .saturating_add(HALF_ROUTER_OFFSET);
+                    .saturating_sub(router_offset)
+                    .saturating_add(half_router_offset);
 
                 let connection_length =
-                    SINKS_SOURCES_CONNECTION_LENGTH.saturating_add(MARKER_HEIGHT);
-                let render_length = SINKS_SOURCES_CONNECTION_LENGTH;
+                    sinks_sources_connection_length.saturating_add(MARKER_HEIGHT);
+                let render_length = sinks_sources_connection_length;
 
                 // Input
                 let input_x = router_x.saturating_add(connection_length);
-                let input_y = router_y.saturating_add(CONNECTION_GAP);
+                let input_y = router_y.saturating_add(connection_gap);
                 let input_s = format!("M{},{} h-{}", input_x, input_y, render_length);
 
                 // Output
-                let output_y = router_y.saturating_sub(CONNECTION_GAP);
+                let output_y = router_y.saturating_sub(connection_gap);
                 let output_s = format!("M{},{} h{}", router_x, output_y, render_length);
 
                 (
@@ -168,22 +197,22 @@ impl Connection {
             }
             Directions::South => {
                 router_x = router_x
-                    .saturating_add(SIDE_LENGTH)
-                    .saturating_sub(HALF_ROUTER_OFFSET);
+                    .saturating_add(side_length)
+                    .saturating_sub(half_router_offset);
                 router_y = router_y
-                    .saturating_sub(ROUTER_OFFSET)
-                    .saturating_add(SIDE_LENGTH);
+                    .saturating_sub(router_offset)
+                    .saturating_add(side_length);
 
-                let render_length = SINKS_SOURCES_CONNECTION_LENGTH.saturating_add(ROUTER_OFFSET);
+                let render_length = sinks_sources_connection_length.saturating_add(router_offset);
                 let connection_length = render_length.saturating_add(MARKER_HEIGHT);
 
                 // Input
-                let input_x = router_x.saturating_add(CONNECTION_GAP);
+                let input_x = router_x.saturating_add(connection_gap);
                 let input_y = router_y.saturating_add(connection_length);
                 let input_s = format!("M{},{} v-{}", input_x, input_y, render_length);
 
                 // Output
-                let output_x = router_x.saturating_sub(CONNECTION_GAP);
+                let output_x = router_x.saturating_sub(connection_gap);
                 let output_s = format!("M{},{} v{}", output_x, router_y, render_length);
 
                 (
@@ -201,19 +230,19 @@ impl Connection {
             }
             Directions::West => {
                 router_y = router_y
-                    .saturating_sub(ROUTER_OFFSET)
-                    .saturating_add(HALF_ROUTER_OFFSET);
+                    .saturating_sub(router_offset)
+                    .saturating_add(half_router_offset);
 
-                let render_length = SINKS_SOURCES_CONNECTION_LENGTH.saturating_add(ROUTER_OFFSET);
+                let render_length = sinks_sources_connection_length.saturating_add(router_offset);
                 let connection_length = render_length.saturating_add(MARKER_HEIGHT);
 
                 // Input
                 let input_x = router_x.saturating_sub(connection_length);
-                let input_y = router_y.saturating_sub(CONNECTION_GAP);
+                let input_y = router_y.saturating_sub(connection_gap);
                 let input_s = format!("M{},{} h{}", input_x, input_y, render_length);
 
                 // Output
-                let output_y = router_y.saturating_add(CONNECTION_GAP);
+                let output_y = router_y.saturating_add(connection_gap);
                 let output_s = format!("M{},{} h-{}", router_x, output_y, render_length);
 
                 (
@@ -234,15 +263,179 @@ impl Connection {
         EdgePath { input, output }
     }
 
-    /// Creates a new [`Connection`] instance given a [`ConnectionPath`]. Remaining parameters are default.
-    fn new(connection_path: ConnectionPath) -> Self {
+    /// Calculates the path for a wrap-around (torus) connection.
+    /// The path leaves the router in the requested direction, travels around the mesh perimeter
+    /// on its own lane and enters the router on the opposite side of the mesh.
+    /// Returns the path and the [`Offsets`] required to fit it in the viewBox.
+    fn get_wrap_path(
+        direction: &Directions,
+        r: &CoordinateT,
+        c: &CoordinateT,
+        rows: &CoordinateT,
+        columns: &CoordinateT,
+        top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> (ConnectionPath, Offsets) {
+        let geometry = processed_base_configuration.geometry();
+        let side_length = *geometry.side_length();
+        let router_offset = *geometry.router_offset();
+        let half_router_offset = *geometry.half_router_offset();
+        let connection_gap = *geometry.connection_gap();
+        let connection_length = *geometry.connection_length();
+
+        let last_row = rows.saturating_sub(1);
+        let last_column = columns.saturating_sub(1);
+
+        let (mut router_x, mut router_y) = Router::get_move_coordinates(r, c, top_left, geometry);
+        let (far_x, far_y) =
+            Router::get_move_coordinates(&last_row, &last_column, top_left, geometry);
+
+        // Mesh perimeter
+        let left = top_left.x().saturating_add(CORE_ROUTER_STROKE_WIDTH);
+        let top = top_left.y().saturating_add(CORE_ROUTER_STROKE_WIDTH);
+        let right = far_x.saturating_add(side_length);
+        let bottom = far_y.saturating_add(side_length);
+
+        // Each wrap-around connection gets its own lane so paths never overlap.
+        let lane = match direction {
+            Directions::East => *r,
+            Directions::West => rows.saturating_add(*r),
+            Directions::North => rows.saturating_mul(2).saturating_add(*c),
+            Directions::South => rows
+                .saturating_mul(2)
+                .saturating_add(*columns)
+                .saturating_add(*c),
+        };
+        let distance = connection_length.saturating_add(lane.saturating_mul(WRAP_LANE_GAP));
+
+        let (path, offsets) = match direction {
+            Directions::North => {
+                router_x = router_x + side_length - half_router_offset + connection_gap;
+                router_y = router_y - router_offset;
+
+                let (_, target_y) = Router::get_move_coordinates(&last_row, c, top_left, geometry);
+                let target_y = target_y - router_offset + side_length;
+
+                (
+                    format!(
+                        "M{},{} V{} H{} V{} H{} V{}",
+                        router_x,
+                        router_y,
+                        top.saturating_sub(distance),
+                        right.saturating_add(distance),
+                        bottom.saturating_add(distance),
+                        router_x,
+                        target_y.saturating_add(MARKER_HEIGHT)
+                    ),
+                    Offsets::new(
+                        left,
+                        top.saturating_sub(distance),
+                        right.saturating_add(distance),
+                        bottom.saturating_add(distance),
+                    ),
+                )
+            }
+            Directions::East => {
+                router_x = router_x + side_length;
+                router_y = router_y - half_router_offset - connection_gap;
+
+                let (target_x, _) = Router::get_move_coordinates(r, &0, top_left, geometry);
+
+                (
+                    format!(
+                        "M{},{} H{} V{} H{} V{} H{}",
+                        router_x,
+                        router_y,
+                        right.saturating_add(distance),
+                        top.saturating_sub(distance),
+                        left.saturating_sub(distance),
+                        router_y,
+                        target_x.saturating_sub(MARKER_HEIGHT)
+                    ),
+                    Offsets::new(
+                        left.saturating_sub(distance),
+                        top.saturating_sub(distance),
+                        right.saturating_add(distance),
+                        bottom,
+                    ),
+                )
+            }
+            Directions::South => {
+                router_x = router_x + side_length - half_router_offset - connection_gap;
+                router_y = router_y - router_offset + side_length;
+
+                let (_, target_y) = Router::get_move_coordinates(&0, c, top_left, geometry);
+                let target_y = target_y - router_offset;
+
+                (
+                    format!(
+                        "M{},{} V{} H{} V{} H{} V{}",
+                        router_x,
+                        router_y,
+                        bottom.saturating_add(distance),
+                        left.saturating_sub(distance),
+                        top.saturating_sub(distance),
+                        router_x,
+                        target_y.saturating_sub(MARKER_HEIGHT)
+                    ),
+                    Offsets::new(
+                        left.saturating_sub(distance),
+                        top.saturating_sub(distance),
+                        right,
+                        bottom.saturating_add(distance),
+                    ),
+                )
+            }
+            Directions::West => {
+                router_y = router_y - half_router_offset + connection_gap;
+
+                let (target_x, _) =
+                    Router::get_move_coordinates(r, &last_column, top_left, geometry);
+                let target_x = target_x + side_length;
+
+                (
+                    format!(
+                        "M{},{} H{} V{} H{} V{} H{}",
+                        router_x,
+                        router_y,
+                        left.saturating_sub(distance),
+                        bottom.saturating_add(distance),
+                        right.saturating_add(distance),
+                        router_y,
+                        target_x.saturating_add(MARKER_HEIGHT)
+                    ),
+                    Offsets::new(
+                        left.saturating_sub(distance),
+                        top,
+                        right.saturating_add(distance),
+                        bottom.saturating_add(distance),
+                    ),
+                )
+            }
+        };
+
+        (
+            ConnectionPath {
+                path,
+                x: router_x,
+                y: router_y,
+            },
+            offsets,
+        )
+    }
+
+    /// Creates a new [`Connection`] instance given its id, [`DataAttributes`] and a [`ConnectionPath`]. Remaining parameters are default.
+    fn new(id: String, data: DataAttributes, connection_path: ConnectionPath) -> Self {
         Self {
+            id,
+            data,
             d: connection_path.path,
-            attributes: CommonAttributes::with_no_class(),
+            attributes: CommonAttributes::with_class(STROKE_CLASS_NAME),
             marker_end: MARKER_REFERENCE,
             x: connection_path.x,
             y: connection_path.y,
             clip_path: USE_FREEFORM_CLIP_PATH,
+            tooltip: None,
         }
     }
 }
@@ -286,6 +479,18 @@ pub(crate) enum ConnectionType {
     Connection(usize),
 }
 
+impl ConnectionType {
+    /// Returns the SVG id of the [`Connection`] this [`ConnectionType`] refers to.
+    /// Edge connections come in pairs, `source` picks the source connection over the sink one.
+    pub(crate) fn connection_id(&self, source: bool) -> String {
+        match self {
+            ConnectionType::Connection(idx) => format!("{CONNECTION_ID_PREFIX}{idx}"),
+            ConnectionType::EdgeConnection(idx) if source => format!("{SOURCE_ID_PREFIX}{idx}"),
+            ConnectionType::EdgeConnection(idx) => format!("{SINK_ID_PREFIX}{idx}"),
+        }
+    }
+}
+
 /// Enum variants to describe a connection direction. Variant content is cardinal [`Directions`].
 #[derive(Hash, PartialEq, Eq)]
 pub(crate) enum DirectionType {
@@ -313,11 +518,19 @@ pub(crate) struct ConnectionsParentGroup {
     /// A double map to quickly retrieve a core's (router) connections in the SVG.
     #[serde(skip)]
     core_connections_map: HashMap<ElementIDT, HashMap<DirectionType, ConnectionType>>,
+    /// Offsets required to fit wrap-around connections, if any, in the viewBox.
+    #[serde(skip)]
+    wrap_offsets: Offsets,
 }
 
 impl ConnectionsParentGroup {
     /// Inserts an SVG core connection in the core_connections_map.
-    fn insert_in_map(&mut self, core_id: &ElementIDT, direction: DirectionType, element: ConnectionType) {
+    fn insert_in_map(
+        &mut self,
+        core_id: &ElementIDT,
+        direction: DirectionType,
+        element: ConnectionType,
+    ) {
         self.core_connections_map
             .entry(*core_id)
             // Each core has 4 connections, so we preallocate 4 slots in the inner map.
@@ -325,6 +538,44 @@ impl ConnectionsParentGroup {
             .insert(direction, element);
     }
 
+    /// Retrieves the inner [`Connection`] leaving a core's router in the provided direction, if any.
+    pub(crate) fn get_output_connection(
+        &self,
+        core_id: &ElementIDT,
+        direction: Directions,
+    ) -> Option<&Connection> {
+        match self
+            .core_connections_map
+            .get(core_id)?
+            .get(&DirectionType::Out(direction))?
+        {
+            ConnectionType::Connection(idx) => self.connections.path.get(*idx),
+            ConnectionType::EdgeConnection(_) => None,
+        }
+    }
+
+    /// Retrieves the [`Connection`] of a core's router in the provided [`DirectionType`], if any.
+    pub(crate) fn get_connection_mut(
+        &mut self,
+        core_id: &ElementIDT,
+        direction_type: &DirectionType,
+    ) -> Option<&mut Connection> {
+        match (
+            self.core_connections_map
+                .get(core_id)?
+                .get(direction_type)?,
+            direction_type,
+        ) {
+            (ConnectionType::Connection(idx), _) => self.connections.path.get_mut(*idx),
+            (ConnectionType::EdgeConnection(idx), DirectionType::Source(_)) => {
+                self.edge_connections.source.get_mut(*idx)
+            }
+            (ConnectionType::EdgeConnection(idx), DirectionType::Out(_)) => {
+                self.edge_connections.sink.get_mut(*idx)
+            }
+        }
+    }
+
     /// Generates edge SVG connections for a given core.
     fn add_edge_connection(
         &mut self,
@@ -333,13 +584,23 @@ impl ConnectionsParentGroup {
         r: &CoordinateT,
         c: &CoordinateT,
         top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) {
-        let EdgePath { input, output } = Connection::get_edge_paths(direction, r, c, top_left);
+        let EdgePath { input, output } =
+            Connection::get_edge_paths(direction, r, c, top_left, processed_base_configuration);
         let current_source_size = self.edge_connections.source.len();
         let current_sink_size = self.edge_connections.sink.len();
 
-        self.edge_connections.source.push(Connection::new(input));
-        self.edge_connections.sink.push(Connection::new(output));
+        self.edge_connections.source.push(Connection::new(
+            format!("{SOURCE_ID_PREFIX}{current_source_size}"),
+            DataAttributes::link(LinkKind::Source, core_id, r, c, direction),
+            input,
+        ));
+        self.edge_connections.sink.push(Connection::new(
+            format!("{SINK_ID_PREFIX}{current_sink_size}"),
+            DataAttributes::link(LinkKind::Sink, core_id, r, c, direction),
+            output,
+        ));
 
         // When we insert in map, we store direction and the index of the element in its
         // respective vector so we can grab it quickly in case we need to display its load,
@@ -364,11 +625,17 @@ impl ConnectionsParentGroup {
         r: &CoordinateT,
         c: &CoordinateT,
         top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) {
-        let path = Connection::get_inner_path(direction, &r, &c, top_left);
+        let path =
+            Connection::get_inner_path(direction, &r, &c, top_left, processed_base_configuration);
         let current_size = self.connections.path.len();
 
-        self.connections.path.push(Connection::new(path));
+        self.connections.path.push(Connection::new(
+            format!("{CONNECTION_ID_PREFIX}{current_size}"),
+            DataAttributes::link(LinkKind::Inner, core_id, r, c, direction),
+            path,
+        ));
 
         // When we insert in map, we store direction and the index of the element in its
         // respective vector so we can grab it quickly in case we need to display its load,
@@ -380,13 +647,90 @@ impl ConnectionsParentGroup {
         );
     }
 
+    /// Generates wrap-around SVG connections (output only) for a given core.
+    /// These are registered as inner connections: as far as channel data goes, they are core->core links.
+    fn add_wrap_connection(
+        &mut self,
+        core_id: &ElementIDT,
+        direction: &Directions,
+        r: &CoordinateT,
+        c: &CoordinateT,
+        rows: &CoordinateT,
+        columns: &CoordinateT,
+        top_left: &TopLeft,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) {
+        let (path, offsets) = Connection::get_wrap_path(
+            direction,
+            r,
+            c,
+            rows,
+            columns,
+            top_left,
+            processed_base_configuration,
+        );
+        let current_size = self.connections.path.len();
+
+        self.connections.path.push(Connection::new(
+            format!("{CONNECTION_ID_PREFIX}{current_size}"),
+            DataAttributes::link(LinkKind::Wrap, core_id, r, c, direction),
+            path,
+        ));
+        self.wrap_offsets.update(offsets);
+
+        self.insert_in_map(
+            core_id,
+            DirectionType::Out(*direction),
+            ConnectionType::Connection(current_size),
+        );
+    }
+
+    /// Generates the SVG connection for a link that leaves the mesh, according to the system [`Topology`].
+    fn add_outer_connection(
+        &mut self,
+        core_id: &ElementIDT,
+        direction: &Directions,
+        r: &CoordinateT,
+        c: &CoordinateT,
+        rows: &CoordinateT,
+        columns: &CoordinateT,
+        top_left: &TopLeft,
+        topology: &Topology,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) {
+        match topology {
+            Topology::Mesh => self.add_edge_connection(
+                core_id,
+                direction,
+                r,
+                c,
+                top_left,
+                processed_base_configuration,
+            ),
+            Topology::Torus => self.add_wrap_connection(
+                core_id,
+                direction,
+                r,
+                c,
+                rows,
+                columns,
+                top_left,
+                processed_base_configuration,
+            ),
+        }
+    }
+
     // Generates a core's SVG connections.
     pub(crate) fn add_connections(
         &mut self,
         core: &Core,
         r: &CoordinateT,
         c: &CoordinateT,
+        rows: &CoordinateT,
+        columns: &CoordinateT,
         top_left: &TopLeft,
+        topology: &Topology,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) {
         // Does this core have edge connections?
         let on_edge = core.matrix_edge();
@@ -395,40 +739,112 @@ impl ConnectionsParentGroup {
         for direction in core.channels().channel().keys() {
             if let Some(edge_position) = on_edge.as_ref() {
                 // Here we match against the direction and the edge position to decide
-                // what kind of SVG connection (inner (core->core) or edge (core->border, border->core)) to generate.
+                // what kind of SVG connection (inner (core->core) or outer) to generate.
+                // Outer connections are either edge (core->border, border->core) or wrap-around (core->core),
+                // depending on the system topology.
                 match direction {
                     Directions::North => match edge_position {
-                        EdgePosition::Top | EdgePosition::TopLeft | EdgePosition::TopRight => {
-                            self.add_edge_connection(core.id(), direction, r, c, top_left)
-                        }
-                        _ => self.add_inner_connection(core.id(), direction, r, c, top_left),
+                        EdgePosition::Top | EdgePosition::TopLeft | EdgePosition::TopRight => self
+                            .add_outer_connection(
+                                core.id(),
+                                direction,
+                                r,
+                                c,
+                                rows,
+                                columns,
+                                top_left,
+                                topology,
+                                processed_base_configuration,
+                            ),
+                        _ => self.add_inner_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            top_left,
+                            processed_base_configuration,
+                        ),
                     },
                     Directions::East => match edge_position {
                         EdgePosition::Right
                         | EdgePosition::TopRight
-                        | EdgePosition::BottomRight => {
-                            self.add_edge_connection(core.id(), direction, r, c, top_left)
-                        }
-                        _ => self.add_inner_connection(core.id(), direction, r, c, top_left),
+                        | EdgePosition::BottomRight => self.add_outer_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            rows,
+                            columns,
+                            top_left,
+                            topology,
+                            processed_base_configuration,
+                        ),
+                        _ => self.add_inner_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            top_left,
+                            processed_base_configuration,
+                        ),
                     },
                     Directions::South => match edge_position {
                         EdgePosition::Bottom
                         | EdgePosition::BottomLeft
-                        | EdgePosition::BottomRight => {
-                            self.add_edge_connection(core.id(), direction, r, c, top_left)
-                        }
-                        _ => self.add_inner_connection(core.id(), direction, r, c, top_left),
+                        | EdgePosition::BottomRight => self.add_outer_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            rows,
+                            columns,
+                            top_left,
+                            topology,
+                            processed_base_configuration,
+                        ),
+                        _ => self.add_inner_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            top_left,
+                            processed_base_configuration,
+                        ),
                     },
                     Directions::West => match edge_position {
                         EdgePosition::Left | EdgePosition::TopLeft | EdgePosition::BottomLeft => {
-                            self.add_edge_connection(core.id(), direction, r, c, top_left)
+                            self.add_outer_connection(
+                                core.id(),
+                                direction,
+                                r,
+                                c,
+                                rows,
+                                columns,
+                                top_left,
+                                topology,
+                                processed_base_configuration,
+                            )
                         }
-                        _ => self.add_inner_connection(core.id(), direction, r, c, top_left),
+                        _ => self.add_inner_connection(
+                            core.id(),
+                            direction,
+                            r,
+                            c,
+                            top_left,
+                            processed_base_configuration,
+                        ),
                     },
                 }
             } else {
                 // This core is not on edge so this is just a core->core connection.
-                self.add_inner_connection(core.id(), direction, r, c, top_left);
+                self.add_inner_connection(
+                    core.id(),
+                    direction,
+                    r,
+                    c,
+                    top_left,
+                    processed_base_configuration,
+                );
             }
         }
     }
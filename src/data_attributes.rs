@@ -0,0 +1,139 @@
+use manycore_parser::{Directions, ElementIDT, SinkSourceDirection};
+use serde::Serialize;
+
+use crate::CoordinateT;
+
+/// Possible values of the `data-link-kind` attribute on a [`crate::Connection`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum LinkKind {
+    /// Core to core link.
+    Inner,
+    /// Torus link that wraps around the matrix.
+    Wrap,
+    /// Border router to core link.
+    Source,
+    /// Core to border router link.
+    Sink,
+}
+
+impl LinkKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::Inner => "inner",
+            LinkKind::Wrap => "wrap",
+            LinkKind::Source => "source",
+            LinkKind::Sink => "sink",
+        }
+    }
+}
+
+/// Lowercase name of a [`Directions`] variant.
+pub(crate) fn direction_str(direction: &Directions) -> &'static str {
+    match direction {
+        Directions::North => "north",
+        Directions::East => "east",
+        Directions::South => "south",
+        Directions::West => "west",
+    }
+}
+
+/// Lowercase name of a [`SinkSourceDirection`] variant.
+fn sink_source_direction_str(direction: &SinkSourceDirection) -> &'static str {
+    match direction {
+        SinkSourceDirection::North => "north",
+        SinkSourceDirection::East => "east",
+        SinkSourceDirection::South => "south",
+        SinkSourceDirection::West => "west",
+    }
+}
+
+/// `data-*` attributes that describe what an SVG element represents.
+/// They give the front-end a stable way to bind to elements that does not depend on DOM order.
+#[derive(Serialize, Debug)]
+pub(crate) struct DataAttributes {
+    #[serde(rename = "@data-kind")]
+    kind: &'static str,
+    #[serde(rename = "@data-link-kind", skip_serializing_if = "Option::is_none")]
+    link_kind: Option<&'static str>,
+    #[serde(rename = "@data-task-id", skip_serializing_if = "Option::is_none")]
+    task_id: Option<u16>,
+    #[serde(rename = "@data-core-id")]
+    core_id: ElementIDT,
+    #[serde(rename = "@data-row")]
+    row: CoordinateT,
+    #[serde(rename = "@data-col")]
+    col: CoordinateT,
+    #[serde(rename = "@data-direction", skip_serializing_if = "Option::is_none")]
+    direction: Option<&'static str>,
+}
+
+impl DataAttributes {
+    /// Attributes shared by every element, the ones that only apply to some are left empty.
+    fn base(
+        kind: &'static str,
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        col: &CoordinateT,
+    ) -> Self {
+        Self {
+            kind,
+            link_kind: None,
+            task_id: None,
+            core_id: *core_id,
+            row: *row,
+            col: *col,
+            direction: None,
+        }
+    }
+
+    /// Generates the [`DataAttributes`] of a core.
+    pub(crate) fn core(core_id: &ElementIDT, row: &CoordinateT, col: &CoordinateT) -> Self {
+        Self::base("core", core_id, row, col)
+    }
+
+    /// Generates the [`DataAttributes`] of a router.
+    pub(crate) fn router(core_id: &ElementIDT, row: &CoordinateT, col: &CoordinateT) -> Self {
+        Self::base("router", core_id, row, col)
+    }
+
+    /// Generates the [`DataAttributes`] of a link connected to a core's router.
+    pub(crate) fn link(
+        link_kind: LinkKind,
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        col: &CoordinateT,
+        direction: &Directions,
+    ) -> Self {
+        Self {
+            link_kind: Some(link_kind.as_str()),
+            direction: Some(direction_str(direction)),
+            ..Self::base("link", core_id, row, col)
+        }
+    }
+
+    /// Generates the [`DataAttributes`] of a task allocated to a core.
+    pub(crate) fn task(
+        task_id: &u16,
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        col: &CoordinateT,
+    ) -> Self {
+        Self {
+            task_id: Some(*task_id),
+            ..Self::base("task", core_id, row, col)
+        }
+    }
+
+    /// Generates the [`DataAttributes`] of a border router connected to a core's router.
+    pub(crate) fn border_router(
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        col: &CoordinateT,
+        direction: &SinkSourceDirection,
+    ) -> Self {
+        Self {
+            direction: Some(sink_source_direction_str(direction)),
+            ..Self::base("borderRouter", core_id, row, col)
+        }
+    }
+}
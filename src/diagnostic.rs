@@ -0,0 +1,70 @@
+use getset::Getters;
+use serde::Serialize;
+
+#[cfg(doc)]
+use crate::{Configuration, UpdateResult, SVG};
+
+/// A non-fatal issue found while applying a [`Configuration`] to an [`SVG`].
+/// The affected information is skipped, and the issue is reported in the [`UpdateResult`].
+#[derive(Serialize, Getters, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+#[getset(get = "pub")]
+pub struct Diagnostic {
+    /// The element the attribute belongs to, e.g. `c3` for core 3, `r3` for router 3 or `North channel of c3`.
+    element: String,
+    /// The attribute key.
+    attribute: String,
+    /// The attribute value as found in the ManyCore system.
+    value: String,
+    /// What went wrong.
+    reason: String,
+}
+
+impl Diagnostic {
+    /// Generates a [`Diagnostic`] for an attribute value that could not be parsed as a number.
+    pub(crate) fn unparseable_number(element: String, attribute: &str, value: &str) -> Self {
+        Self {
+            element,
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+            reason: String::from("Value is not a finite number, it could not be coloured."),
+        }
+    }
+
+    /// Generates a [`Diagnostic`] for a core or router label that does not fit inside its element.
+    pub(crate) fn overflowing_label(
+        element: String,
+        attribute: &str,
+        value: &str,
+        reason: &str,
+    ) -> Self {
+        Self {
+            element,
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Generates a [`Diagnostic`] for a source load on a torus wrap-around link, which has no source link to display it on.
+    pub(crate) fn dropped_source_load(element: String, value: String) -> Self {
+        Self {
+            element,
+            attribute: String::from("sourceLoad"),
+            value,
+            reason: String::from(
+                "Wrap-around links have no source link, the source load is not displayed.",
+            ),
+        }
+    }
+
+    /// Generates a [`Diagnostic`] for a channel label that overlaps other labels wherever it is placed.
+    pub(crate) fn unplaced_label(element: String, value: &str) -> Self {
+        Self {
+            element,
+            attribute: String::from("label"),
+            value: value.to_string(),
+            reason: String::from("Label could not be placed without overlapping other labels."),
+        }
+    }
+}
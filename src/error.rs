@@ -23,6 +23,8 @@ pub enum SVGErrorKind {
     DataConversionError(String),
     /// A generic [`SVG`] generation error.
     GenerationError(String),
+    /// Converting the [`SVG`] to another format, e.g. PNG, failed.
+    ExportError(String),
 }
 
 /// A generic error container used to keep results consistent within the library.
@@ -51,6 +53,7 @@ impl Display for SVGError {
                 write!(f, "Data Conversion Error: {reason}")
             }
             SVGErrorKind::GenerationError(reason) => write!(f, "Generation Error: {reason}"),
+            SVGErrorKind::ExportError(reason) => write!(f, "Export Error: {reason}"),
         }
     }
 }
@@ -60,6 +63,12 @@ pub(crate) fn generation_error(reason: String) -> SVGError {
     SVGError::new(SVGErrorKind::GenerationError(reason))
 }
 
+/// Utility to create an export error.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn export_error(reason: String) -> SVGError {
+    SVGError::new(SVGErrorKind::ExportError(reason))
+}
+
 impl Error for SVGError {}
 
 impl From<ManycoreError> for SVGError {
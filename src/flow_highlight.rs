@@ -0,0 +1,360 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use manycore_parser::{
+    Directions, ElementIDT, ManycoreSystem, RoutingAlgorithms, RoutingMap, RoutingType, WithID,
+};
+use quick_xml::DeError;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    partial_update::PartialUpdate, ConnectionsParentGroup, CoordinateT, ProcessingParentGroup,
+    SVGError, SVGErrorKind, SerialiseOptions, Topology, MARKER_REFERENCE,
+};
+
+#[cfg(doc)]
+use crate::SVG;
+
+static FLOW_HIGHLIGHT_ID: &'static str = "flowHighlight";
+static HIGHLIGHT_STROKE: &'static str = "#e11d48";
+static HIGHLIGHT_STROKE_WIDTH: &'static str = "4";
+// Everything in the main group but the highlight is dimmed.
+static DIM_CSS: &'static str = "#mainGroup > g:not(#flowHighlight) {opacity: 0.25;}";
+
+/// The flow to highlight in the [`SVG`].
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(tag = "type")]
+pub enum FlowSelection {
+    /// A task graph edge, by sender and receiver task IDs.
+    TaskEdge { from: u16, to: u16 },
+    /// A source and destination core pair, by core ID.
+    /// With [`RoutingAlgorithms::Observed`], traffic must have been observed between the two.
+    Cores {
+        source: ElementIDT,
+        destination: ElementIDT,
+    },
+}
+
+/// Object representation of the `<style>` that dims everything but the highlight.
+#[derive(Serialize)]
+struct DimStyle {
+    #[serde(rename = "$text")]
+    css: &'static str,
+}
+
+/// Object representation of a highlighted router-to-router connection `<path>`.
+#[derive(Serialize)]
+struct HighlightedHop {
+    #[serde(rename = "@d")]
+    d: String,
+    #[serde(rename = "@fill")]
+    fill: &'static str,
+    #[serde(rename = "@stroke")]
+    stroke: &'static str,
+    #[serde(rename = "@stroke-width")]
+    stroke_width: &'static str,
+    #[serde(rename = "@marker-end")]
+    marker_end: &'static str,
+}
+
+/// Object representation of the SVG `<g>` that highlights the routed path of a single flow.
+#[derive(Serialize)]
+#[serde(rename = "g")]
+pub(crate) struct FlowHighlight {
+    #[serde(rename = "@id")]
+    id: &'static str,
+    style: DimStyle,
+    #[serde(rename = "path")]
+    hops: Vec<HighlightedHop>,
+}
+
+/// Error thrown when a core or task in a [`FlowSelection`] is not part of the [`ManycoreSystem`].
+fn missing_flow_endpoint(reason: String) -> SVGError {
+    SVGError::new(SVGErrorKind::ManycoreMismatch(reason))
+}
+
+/// Error thrown when the observed channels don't connect the two ends of a [`FlowSelection`].
+fn unobserved_flow(source: &ElementIDT, destination: &ElementIDT) -> SVGError {
+    SVGError::new(SVGErrorKind::ManycoreMismatch(format!(
+        "No observed traffic goes from Core {} to Core {}.",
+        source, destination
+    )))
+}
+
+/// How cores are laid out and connected, to compute or trace a flow's path.
+struct FlowGraph<'a> {
+    connections_group: &'a ConnectionsParentGroup,
+    topology: Topology,
+    rows: CoordinateT,
+    columns: CoordinateT,
+    // Core ID -> (row, column) and back.
+    core_coordinates: BTreeMap<ElementIDT, (CoordinateT, CoordinateT)>,
+    cores_at: BTreeMap<(CoordinateT, CoordinateT), ElementIDT>,
+}
+
+impl FlowGraph<'_> {
+    /// Returns the ID of the core at the other end of a core's output link, if the link connects two cores.
+    /// Torus wrap-around links connect to the core on the opposite side of the matrix.
+    fn neighbour(&self, core_id: &ElementIDT, direction: Directions) -> Option<ElementIDT> {
+        self.connections_group
+            .get_output_connection(core_id, direction)?;
+
+        let (r, c) = self.core_coordinates.get(core_id)?;
+        let (r, c) = match direction {
+            Directions::North => (r - 1, *c),
+            Directions::East => (*r, c + 1),
+            Directions::South => (r + 1, *c),
+            Directions::West => (*r, c - 1),
+        };
+
+        self.cores_at
+            .get(&(r.rem_euclid(self.rows), c.rem_euclid(self.columns)))
+            .copied()
+    }
+
+    /// Number of hops between two cores on a shortest path, wrap-around links included.
+    fn distance(&self, from: &ElementIDT, to: &ElementIDT) -> CoordinateT {
+        let ((from_r, from_c), (to_r, to_c)) = match (
+            self.core_coordinates.get(from),
+            self.core_coordinates.get(to),
+        ) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return CoordinateT::MAX,
+        };
+
+        let along = |from: &CoordinateT, to: &CoordinateT, size: CoordinateT| {
+            let straight = (from - to).abs();
+
+            match self.topology {
+                Topology::Mesh => straight,
+                Topology::Torus => straight.min(size - straight),
+            }
+        };
+
+        along(from_r, to_r, self.rows) + along(from_c, to_c, self.columns)
+    }
+
+    /// Computes the path of a dimension ordered flow one hop at a time: [`RoutingAlgorithms::RowFirst`]
+    /// changes row (North, South) first, [`RoutingAlgorithms::ColumnFirst`] changes column (East, West) first.
+    /// Routing does not know of wrap-around links, so the path never takes them.
+    fn dimension_ordered(
+        &self,
+        source: ElementIDT,
+        destination: ElementIDT,
+        rows_first: bool,
+    ) -> Result<Vec<(ElementIDT, Directions)>, SVGError> {
+        let missing_core = |row: CoordinateT, column: CoordinateT| {
+            missing_flow_endpoint(format!("Could not find a core at ({row},{column})."))
+        };
+
+        // Both ends are known to be part of the system.
+        let (mut row, mut column) = self.core_coordinates[&source];
+        let (to_row, to_column) = self.core_coordinates[&destination];
+
+        let mut current = source;
+        let mut hops = Vec::new();
+        for vertical in [rows_first, !rows_first] {
+            loop {
+                let direction = match (vertical, row.cmp(&to_row), column.cmp(&to_column)) {
+                    (true, Ordering::Less, _) => Directions::South,
+                    (true, Ordering::Greater, _) => Directions::North,
+                    (false, _, Ordering::Less) => Directions::East,
+                    (false, _, Ordering::Greater) => Directions::West,
+                    _ => break,
+                };
+
+                hops.push((current, direction));
+
+                match direction {
+                    Directions::North => row -= 1,
+                    Directions::South => row += 1,
+                    Directions::West => column -= 1,
+                    Directions::East => column += 1,
+                }
+                current = *self
+                    .cores_at
+                    .get(&(row, column))
+                    .ok_or_else(|| missing_core(row, column))?;
+            }
+        }
+
+        Ok(hops)
+    }
+
+    /// Follows the observed output channels from `current` to `destination`, depth first.
+    /// Channels getting closer to the destination are tried first.
+    /// Observed routing only records which channels carried traffic, not which flow did:
+    /// at routers shared by several flows, the path can follow the channels of another flow.
+    /// Returns whether the destination was reached, in which case `hops` holds the path.
+    fn trace(
+        &self,
+        routing_map: &RoutingMap,
+        current: ElementIDT,
+        destination: ElementIDT,
+        visited: &mut BTreeSet<ElementIDT>,
+        hops: &mut Vec<(ElementIDT, Directions)>,
+    ) -> bool {
+        if current == destination {
+            return true;
+        }
+
+        let mut candidates: Vec<(Directions, ElementIDT)> = routing_map
+            .get(&current)
+            .into_iter()
+            .flatten()
+            .filter(|(target, _)| matches!(target, RoutingType::OutputChannel))
+            .flat_map(|(_, directions)| directions)
+            .filter_map(|direction| {
+                self.neighbour(&current, *direction)
+                    .map(|neighbour| (*direction, neighbour))
+            })
+            .filter(|(_, neighbour)| !visited.contains(neighbour))
+            .collect();
+        candidates.sort_by_key(|(_, neighbour)| self.distance(neighbour, &destination));
+
+        for (direction, neighbour) in candidates {
+            // Another candidate might have reached it in the meantime.
+            if !visited.insert(neighbour) {
+                continue;
+            }
+
+            hops.push((current, direction));
+            if self.trace(routing_map, neighbour, destination, visited, hops) {
+                return true;
+            }
+            hops.pop();
+        }
+
+        false
+    }
+}
+
+impl FlowHighlight {
+    /// Generates a new [`FlowHighlight`] for the provided [`FlowSelection`].
+    /// Dimension ordered paths are computed hop by hop. [`RoutingAlgorithms::Observed`] paths are traced
+    /// through the channels the [`ManycoreSystem`] observed traffic on, see [`FlowGraph::trace`].
+    pub(crate) fn new(
+        manycore: &mut ManycoreSystem,
+        flow: &FlowSelection,
+        algorithm: &RoutingAlgorithms,
+        topology: Topology,
+        processing_groups: &ProcessingParentGroup,
+        connections_group: &ConnectionsParentGroup,
+    ) -> Result<Self, SVGError> {
+        let mut core_coordinates: BTreeMap<ElementIDT, (CoordinateT, CoordinateT)> =
+            BTreeMap::new();
+        let mut cores_at: BTreeMap<(CoordinateT, CoordinateT), ElementIDT> = BTreeMap::new();
+        let mut task_cores: BTreeMap<u16, ElementIDT> = BTreeMap::new();
+
+        for (core, processing_group) in manycore
+            .cores()
+            .list()
+            .iter()
+            .zip(processing_groups.g().iter())
+        {
+            let (r, c) = processing_group.coordinates();
+            core_coordinates.insert(*core.id(), (*r, *c));
+            cores_at.insert((*r, *c), *core.id());
+
+            if let Some(task_id) = core.allocated_task() {
+                task_cores.insert(*task_id, *core.id());
+            }
+        }
+
+        let (source, destination) = match flow {
+            FlowSelection::TaskEdge { from, to } => {
+                let task_core = |task_id: &u16| {
+                    task_cores.get(task_id).copied().ok_or_else(|| {
+                        missing_flow_endpoint(format!(
+                            "Task {task_id} is not allocated to any core."
+                        ))
+                    })
+                };
+
+                (task_core(from)?, task_core(to)?)
+            }
+            FlowSelection::Cores {
+                source,
+                destination,
+            } => (*source, *destination),
+        };
+
+        for core_id in [&source, &destination] {
+            if !core_coordinates.contains_key(core_id) {
+                return Err(missing_flow_endpoint(format!(
+                    "Could not find Core {core_id}."
+                )));
+            }
+        }
+
+        let graph = FlowGraph {
+            connections_group,
+            topology,
+            rows: CoordinateT::from(*manycore.rows()),
+            columns: CoordinateT::from(*manycore.columns()),
+            core_coordinates,
+            cores_at,
+        };
+
+        let hops = match algorithm {
+            RoutingAlgorithms::RowFirst => graph.dimension_ordered(source, destination, true)?,
+            RoutingAlgorithms::ColumnFirst => {
+                graph.dimension_ordered(source, destination, false)?
+            }
+            RoutingAlgorithms::Observed => {
+                let routing_map = manycore.route(algorithm)?;
+
+                let mut hops = Vec::new();
+                let mut visited = BTreeSet::from([source]);
+                if !graph.trace(&routing_map, source, destination, &mut visited, &mut hops) {
+                    return Err(unobserved_flow(&source, &destination));
+                }
+
+                hops
+            }
+        };
+
+        // Every hop must leave through an existing output connection.
+        let hops = hops
+            .into_iter()
+            .map(|(core_id, direction)| {
+                let connection = connections_group
+                    .get_output_connection(&core_id, direction)
+                    .ok_or_else(|| {
+                        SVGError::new(SVGErrorKind::ConnectionError(format!(
+                            "Could not retrieve {} connection for Core {}",
+                            direction, core_id
+                        )))
+                    })?;
+
+                Ok(HighlightedHop {
+                    d: connection.d().clone(),
+                    fill: "none",
+                    stroke: HIGHLIGHT_STROKE,
+                    stroke_width: HIGHLIGHT_STROKE_WIDTH,
+                    marker_end: MARKER_REFERENCE,
+                })
+            })
+            .collect::<Result<Vec<HighlightedHop>, SVGError>>()?;
+
+        Ok(Self {
+            id: FLOW_HIGHLIGHT_ID,
+            style: DimStyle { css: DIM_CSS },
+            hops,
+        })
+    }
+}
+
+impl PartialUpdate for FlowHighlight {
+    fn write_update<W: fmt::Write>(
+        &self,
+        mut writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), DeError> {
+        options.write_fragment(&self.style, Some("style"), &mut writer)?;
+        options.write_fragment(&self.hops, Some("path"), &mut writer)
+    }
+}
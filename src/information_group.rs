@@ -1,32 +1,63 @@
-use getset::MutGetters;
+use std::fmt;
+
+use getset::{MutGetters, Setters};
 use quick_xml::DeError;
 use serde::Serialize;
 
-use crate::{partial_update::PartialUpdate, InformationLayer};
+use crate::{
+    partial_update::PartialUpdate, CommunicationsOverlay, GroupLabel, InformationLayer, Legend,
+    SerialiseOptions,
+};
 
-#[derive(Serialize, MutGetters)]
+#[derive(Serialize, MutGetters, Setters)]
 pub(crate) struct InformationGroup {
+    #[serde(rename = "g", skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    communications: Option<CommunicationsOverlay>,
     #[serde(rename = "g", skip_serializing_if = "Vec::is_empty")]
     #[getset(get_mut = "pub")]
     groups: Vec<InformationLayer>,
+    #[serde(rename = "g", skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    legend: Option<Legend>,
     #[serde(rename = "@id")]
-    id: &'static str,
+    #[getset(set = "pub")]
+    id: String,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    label: Option<GroupLabel>,
 }
 
 impl InformationGroup {
     /// Creates a new [`InformationGroup`] with capacity for each [`ProcessingGroup`].
     pub(crate) fn new(number_of_cores: &usize) -> Self {
         Self {
+            communications: None,
             groups: Vec::with_capacity(*number_of_cores),
-            id: "information",
+            legend: None,
+            id: String::from("information"),
+            label: None,
         }
     }
 }
 
 impl PartialUpdate for InformationGroup {
-    fn update_string(&self) -> Result<String, DeError> {
-        let groups = quick_xml::se::to_string_with_root("g", &self.groups)?;
+    fn write_update<W: fmt::Write>(
+        &self,
+        mut writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), DeError> {
+        // The overlay goes first so that information text is drawn above it.
+        if let Some(communications) = &self.communications {
+            options.write_fragment(communications, None, &mut writer)?;
+        }
+
+        options.write_fragment(&self.groups, Some("g"), &mut writer)?;
+
+        if let Some(legend) = &self.legend {
+            options.write_fragment(legend, None, &mut writer)?;
+        }
 
-        Ok(groups)
+        Ok(())
     }
 }
@@ -1,9 +1,9 @@
-use manycore_parser::{RoutingMap, SystemDimensionsT, WithID};
+use manycore_parser::{ElementIDT, RoutingMap, SystemDimensionsT, WithID};
 use serde::Serialize;
 
 use crate::{
-    ClipPath, Configuration, ConnectionsParentGroup, CoordinateT, Offsets,
-    ProcessedBaseConfiguration, ProcessingGroup, RoutingConfiguration, SVGError, ROUTER_OFFSET,
+    style::ElementRule, ClipPath, Configuration, ConnectionsParentGroup, CoordinateT, Diagnostic,
+    Offsets, ProcessedBaseConfiguration, ProcessingGroup, RoutingConfiguration, SVGError,
     USE_FREEFORM_CLIP_PATH,
 };
 
@@ -14,6 +14,9 @@ static OFFSET_FROM_BORDER: CoordinateT = 1;
 struct ProcessingInformation {
     #[serde(rename = "@clip-path")]
     clip_path: String,
+    /// Full text of the labels that did not fit, shown on hover.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
     #[serde(rename = "text")]
     information: Vec<TextInformation>,
 }
@@ -34,8 +37,14 @@ pub(crate) struct InformationLayer {
     coordinates: Option<TextInformation>,
     #[serde(rename = "text", skip_serializing_if = "Vec::is_empty")]
     links_load: Vec<TextInformation>,
+    #[serde(rename = "line", skip_serializing_if = "Vec::is_empty")]
+    leader_lines: Vec<LeaderLine>,
+    /// The core this layer describes.
+    #[serde(skip)]
+    core_id: ElementIDT,
 }
 
+mod colour_interpolation;
 mod utils;
 use utils::*;
 mod text_information;
@@ -44,6 +53,11 @@ mod coordinates;
 use coordinates::make_coordinates;
 mod channel_data;
 use channel_data::*;
+mod diff;
+mod label_placement;
+use label_placement::LeaderLine;
+mod label_fitting;
+use label_fitting::LabelStack;
 
 impl InformationLayer {
     /// Generates a new [`InformationLayer`] instance.
@@ -52,15 +66,18 @@ impl InformationLayer {
         configuration: &mut Configuration,
         core: &manycore_parser::Core,
         links_with_load: Option<&RoutingMap>,
-        css: &mut String,
+        element_rules: &mut Vec<ElementRule>,
         processing_group: &ProcessingGroup,
         connections_group: &ConnectionsParentGroup,
         routing_configuration: Option<&RoutingConfiguration>,
+        border_routers: Option<&[Offsets]>,
         offsets: &mut Offsets,
+        diagnostics: &mut Vec<Diagnostic>,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Result<Self, SVGError> {
         let mut ret = InformationLayer::default();
         ret.clip_path = USE_FREEFORM_CLIP_PATH;
+        ret.core_id = *core.id();
 
         let (r, c) = processing_group.coordinates();
         let (core_x, core_y) = processing_group.core().move_coordinates();
@@ -77,6 +94,8 @@ impl InformationLayer {
             processed_base_configuration,
         )?;
 
+        let geometry = processed_base_configuration.geometry();
+
         // Core
         generate_with_id(
             *core_x,
@@ -86,7 +105,9 @@ impl InformationLayer {
             core,
             &mut ret.core_group,
             "start",
-            css,
+            &ClipPath::for_core(*core.id(), *core_x, *core_y, geometry),
+            element_rules,
+            diagnostics,
             processed_base_configuration,
         )?;
         // Clip path id
@@ -96,13 +117,15 @@ impl InformationLayer {
         let (router_x, router_y) = processing_group.router().move_coordinates();
         generate_with_id(
             *router_x,
-            router_y - ROUTER_OFFSET,
+            router_y - geometry.router_offset(),
             configuration.router_config(),
             configuration.router_fills(),
             core.router(),
             &mut ret.router_group,
             "start",
-            css,
+            &ClipPath::for_router(*core.router().id(), *router_x, *router_y, geometry),
+            element_rules,
+            diagnostics,
             processed_base_configuration,
         )?;
         // Clip path id
@@ -116,8 +139,11 @@ impl InformationLayer {
             links_with_load,
             connections_group,
             routing_configuration,
+            border_routers,
             offsets,
             &mut ret,
+            element_rules,
+            diagnostics,
             processed_base_configuration,
         )?;
 
@@ -1,22 +1,29 @@
-use std::collections::{btree_map::Iter, BTreeSet};
+use std::collections::BTreeSet;
 
 use manycore_parser::{
     Channel, Core, Directions, RoutingMap, RoutingType, WithID, WithXMLAttributes,
 };
 
 use crate::{
-    Configuration, ConnectionType, ConnectionsParentGroup, CoordinateT, DirectionType,
-    FieldConfiguration, InformationLayer, Offsets, ProcessedBaseConfiguration,
-    RoutingConfiguration, SVGError, TextInformation,
+    style::ElementRule, Configuration, ConnectionType, ConnectionsParentGroup, CoordinateT,
+    Diagnostic, DirectionType, FieldConfiguration, InformationLayer, LinkSide, LoadConfiguration,
+    Offsets, ProcessedBaseConfiguration, RoutingConfiguration, SVGError, TextInformation,
 };
 
+static HEATMAP_MINIMUM_STROKE_WIDTH: f32 = 1.0;
+static HEATMAP_MAXIMUM_STROKE_WIDTH: f32 = 4.0;
+/// How many slots attributes can take next to a link, when not explicitly listed.
+static FALLBACK_SLOTS: CoordinateT = 2;
+/// How many nudges towards its core an edge link label can take to clear border routers.
+static MAX_EDGE_NUDGES: CoordinateT = 10;
+
 use super::{
-    get_connection_type, missing_channel, missing_connection, missing_source_load,
-    missing_source_loads,
+    channel_element, get_connection_type, layout_direction, missing_channel, missing_connection,
+    missing_source_load, missing_source_loads, opposite_direction,
 };
 
 /// Utility to retrieve an SVG connection's coordinates and whether it is an edge or an inner connection.
-fn channel_info_details<'a>(
+pub(crate) fn channel_info_details<'a>(
     direction: &Directions,
     connections_group: &'a ConnectionsParentGroup,
     core: &manycore_parser::Core,
@@ -58,43 +65,234 @@ fn channel_info_details<'a>(
     }
 }
 
-/// Generates an SVG channel secondary [`TextInformation`], if requested and present.
-fn get_secondary_channel_attribute(
+/// Generates the heatmap CSS rule for a channel connection.
+/// Links are coloured according to the load colours and get thicker as their load grows.
+fn heatmap_rule(
+    direction: &Directions,
+    connections_group: &ConnectionsParentGroup,
+    core: &Core,
+    routing_type: &RoutingType,
+    load: &u16,
+    bandwidth: &u16,
+    routing_configuration: &RoutingConfiguration,
+) -> Result<ElementRule, SVGError> {
+    let connection_id = get_connection_type(
+        connections_group,
+        &DirectionType::Out(*direction),
+        core.id(),
+    )?
+    .connection_id(matches!(routing_type, RoutingType::SourceChannel));
+
+    let (percentage, fill) =
+        TextInformation::calculate_load_fill_and_percentage(load, bandwidth, routing_configuration);
+
+    // Channels with no bandwidth are overloaded.
+    let stroke_width = match percentage {
+        Some(percentage) => {
+            HEATMAP_MINIMUM_STROKE_WIDTH
+                + (HEATMAP_MAXIMUM_STROKE_WIDTH - HEATMAP_MINIMUM_STROKE_WIDTH)
+                    * f32::from(percentage.min(100))
+                    / 100.0
+        }
+        None => HEATMAP_MAXIMUM_STROKE_WIDTH,
+    };
+
+    let mut declarations = vec![("stroke-width", format!("{:.1}", stroke_width))];
+    if let Some(fill) = fill {
+        declarations.push(("stroke", fill));
+    }
+
+    Ok(ElementRule::new(connection_id, declarations))
+}
+
+/// A channel attribute to display, resolved against the channel configuration.
+pub(crate) struct ChannelLabel<'a> {
+    pub(crate) key: &'a String,
+    pub(crate) field_configuration: &'a FieldConfiguration,
+    pub(crate) side: LinkSide,
+    /// Position of the label along its side of the link, in attribute font sizes.
+    pub(crate) slot: CoordinateT,
+}
+
+/// Resolves which channel attributes to display, in order, and where.
+/// Attributes listed in the [`Configuration`] are stacked on their side of the link, starting from `first_slot`
+/// on the primary side. Otherwise, the `fallback` attributes fill the primary side up to its second slot.
+pub(crate) fn channel_labels<'a>(
+    configuration: &'a Configuration,
+    fallback: impl Iterator<Item = (&'a String, &'a FieldConfiguration)>,
+    first_slot: CoordinateT,
+) -> Vec<ChannelLabel<'a>> {
+    if configuration.channel_attributes().is_empty() {
+        return fallback
+            .zip(first_slot..FALLBACK_SLOTS)
+            .map(|((key, field_configuration), slot)| ChannelLabel {
+                key,
+                field_configuration,
+                side: LinkSide::Primary,
+                slot,
+            })
+            .collect();
+    }
+
+    let mut primary_slot = first_slot;
+    let mut opposite_slot = 0;
+
+    configuration
+        .channel_attributes()
+        .iter()
+        .filter_map(|channel_attribute| {
+            // Attributes that are not configured can't be displayed.
+            let (key, field_configuration) = configuration
+                .channel_config()
+                .get_key_value(channel_attribute.key())?;
+            let slot = match channel_attribute.side() {
+                LinkSide::Primary => &mut primary_slot,
+                LinkSide::Opposite => &mut opposite_slot,
+            };
+            let ret = ChannelLabel {
+                key,
+                field_configuration,
+                side: *channel_attribute.side(),
+                slot: *slot,
+            };
+            *slot = slot.saturating_add(1);
+
+            Some(ret)
+        })
+        .collect()
+}
+
+/// Moves an edge link `label` towards its core, half a font size at a time, until it clears every border router.
+/// The label is left where it was if it can't be cleared.
+fn clear_border_routers(
+    label: &mut TextInformation,
+    direction: &Directions,
+    border_routers: &[Offsets],
+    processed_base_configuration: &ProcessedBaseConfiguration,
+) {
+    let is_clear = |label: &TextInformation| {
+        !border_routers
+            .iter()
+            .any(|border_router| border_router.overlaps(&label.bounding_box()))
+    };
+
+    if is_clear(label) {
+        return;
+    }
+
+    let (x, y) = (*label.x(), *label.y());
+    let step = processed_base_configuration
+        .attribute_font_size_coordinate()
+        .saturating_div(2);
+    let (towards_core_x, towards_core_y) = match direction {
+        Directions::North => (0, step),
+        Directions::South => (0, -step),
+        Directions::East => (-step, 0),
+        Directions::West => (step, 0),
+    };
+
+    for nudge in 1..=MAX_EDGE_NUDGES {
+        label.set_x(x.saturating_add(towards_core_x.saturating_mul(nudge)));
+        label.set_y(y.saturating_add(towards_core_y.saturating_mul(nudge)));
+
+        if is_clear(label) {
+            return;
+        }
+    }
+
+    label.set_x(x);
+    label.set_y(y);
+}
+
+/// Adds the [`TextInformation`] of every requested attribute the channel has to `ret`,
+/// next to its output or source (`target`) link. Labels of `edge` links are kept clear of `border_routers`,
+/// which are [`None`] when hidden.
+fn push_channel_attributes(
+    labels: &[ChannelLabel],
+    target: &RoutingType,
     x: &CoordinateT,
     y: &CoordinateT,
     edge: bool,
-    configuration_iterator: &mut Iter<'_, String, FieldConfiguration>,
+    border_routers: Option<&[Offsets]>,
+    core: &Core,
     channel: &Channel,
     direction: &Directions,
-    prrocessed_base_configuration: &ProcessedBaseConfiguration,
-) -> Option<TextInformation> {
-    if !edge {
-        if let (Some((key, field_configuration)), Some(channel_attributes)) =
-            (configuration_iterator.next(), channel.other_attributes())
-        {
-            return match channel_attributes.get(key) {
-                Some(attribute_value) => {
-                    let link_secondary_text = TextInformation::link_secondary(
+    offsets: &mut Offsets,
+    ret: &mut InformationLayer,
+    diagnostics: &mut Vec<Diagnostic>,
+    processed_base_configuration: &ProcessedBaseConfiguration,
+) -> Result<(), SVGError> {
+    let channel_attributes = match channel.other_attributes() {
+        Some(channel_attributes) => channel_attributes,
+        None => return Ok(()),
+    };
+
+    for label in labels {
+        // Not all attributes must be present on every channel I suppose.
+        // Do nothing if this channel does not have the requested one.
+        if let Some(attribute_value) = channel_attributes.get(label.key) {
+            // Source links only exist on edge connections.
+            let mut link_text = match target {
+                RoutingType::OutputChannel => TextInformation::link_attribute(
+                    direction,
+                    x,
+                    y,
+                    core.id(),
+                    label.key,
+                    attribute_value,
+                    edge,
+                    &label.side,
+                    label.slot,
+                    label.field_configuration,
+                    diagnostics,
+                    processed_base_configuration,
+                ),
+                RoutingType::SourceChannel => TextInformation::source_attribute(
+                    direction,
+                    x,
+                    y,
+                    core.id(),
+                    label.key,
+                    attribute_value,
+                    &label.side,
+                    label.slot,
+                    label.field_configuration,
+                    diagnostics,
+                    processed_base_configuration,
+                ),
+            };
+            // Edge link labels are hidden along with border routers.
+            let visible = match (edge, border_routers) {
+                (true, Some(border_routers)) => {
+                    clear_border_routers(
+                        &mut link_text,
                         direction,
-                        x,
-                        y,
-                        attribute_value,
-                        field_configuration,
-                        prrocessed_base_configuration,
+                        border_routers,
+                        processed_base_configuration,
                     );
-
-                    Some(link_secondary_text)
-                }
-                None => {
-                    // Not all attributes must be present on every channel I suppose.
-                    // Do nothing if this channel does not have the requested one.
-                    None
+                    true
                 }
+                (true, None) => false,
+                (false, _) => true,
             };
+
+            // This channel data might need the viewBox extended to be fully displayed.
+            // Source text is laid out from the source point of view.
+            if visible {
+                let text_direction = match target {
+                    RoutingType::OutputChannel => *direction,
+                    RoutingType::SourceChannel => opposite_direction(direction),
+                };
+                offsets.update(Offsets::from_channel(
+                    &link_text,
+                    &layout_direction(&text_direction, &label.side),
+                ));
+            }
+            ret.links_load.push(link_text);
         }
     }
 
-    None
+    Ok(())
 }
 
 pub(crate) fn generate_channel_data(
@@ -103,13 +301,21 @@ pub(crate) fn generate_channel_data(
     links_with_load: Option<&RoutingMap>,
     connections_group: &ConnectionsParentGroup,
     routing_configuration: Option<&RoutingConfiguration>,
+    border_routers: Option<&[Offsets]>,
     offsets: &mut Offsets,
     ret: &mut InformationLayer,
+    element_rules: &mut Vec<ElementRule>,
+    diagnostics: &mut Vec<Diagnostic>,
     processed_base_configuration: &ProcessedBaseConfiguration,
 ) -> Result<(), SVGError> {
-    // We use this set to keep track of directions we can add information to.
+    // We use these sets to keep track of output and source links we can add information to.
     let mut remaining_directions: BTreeSet<&Directions> =
         core.channels().channel().keys().collect();
+    let mut remaining_sources = remaining_directions.clone();
+
+    // The load of routed channels takes the first slot on the primary side of their link.
+    let labels = channel_labels(configuration, configuration.channel_config().iter(), 0);
+    let routed_labels = channel_labels(configuration, configuration.channel_config().iter(), 1);
 
     if let (Some(links_with_load), Some(routing_configuration)) =
         (links_with_load, routing_configuration)
@@ -117,9 +323,34 @@ pub(crate) fn generate_channel_data(
         if let Some(routed_channels) = links_with_load.get(core.id()) {
             for (target, directions) in routed_channels {
                 for direction in directions {
+                    // Source channels only exist on edge connections. Wrap-around connections have none,
+                    // so their source load can't be displayed.
+                    if let RoutingType::SourceChannel = target {
+                        if let ConnectionType::Connection(_) = get_connection_type(
+                            connections_group,
+                            &DirectionType::Out(*direction),
+                            core.id(),
+                        )? {
+                            let load = core
+                                .source_loads()
+                                .as_ref()
+                                .and_then(|source_loads| source_loads.get(direction));
+                            diagnostics.push(Diagnostic::dropped_source_load(
+                                channel_element(core.id(), direction),
+                                load.map_or_else(String::new, u16::to_string),
+                            ));
+
+                            continue;
+                        }
+                    }
+
                     // We explored this one, so we added all available information. Well, not yet, but we will soon.
-                    // We can remove from set
-                    remaining_directions.remove(direction);
+                    // We can remove from set. Source channels share the direction with an output edge link,
+                    // which might still need its attributes.
+                    match target {
+                        RoutingType::OutputChannel => remaining_directions.remove(direction),
+                        RoutingType::SourceChannel => remaining_sources.remove(direction),
+                    };
 
                     // Get channel details
                     let (x, y, edge) =
@@ -132,142 +363,202 @@ pub(crate) fn generate_channel_data(
                         .get(direction)
                         .ok_or(missing_channel(core.id(), &direction))?;
 
-                    // Generate load text
-                    let link_load_text = match target {
-                        RoutingType::OutputChannel => TextInformation::link_load(
+                    // Output channels carry the channel load, source channels the core source load.
+                    let load = match target {
+                        RoutingType::OutputChannel => channel.current_load(),
+                        RoutingType::SourceChannel => core
+                            .source_loads()
+                            .as_ref()
+                            .ok_or(missing_source_loads(core.id()))?
+                            .get(direction)
+                            .ok_or(missing_source_load(core.id(), direction))?,
+                    };
+
+                    if let LoadConfiguration::Heatmap = routing_configuration.load_configuration() {
+                        // Heatmap only styles the connection itself, no text.
+                        element_rules.push(heatmap_rule(
                             direction,
-                            x,
-                            y,
-                            channel.current_load(),
+                            connections_group,
+                            core,
+                            target,
+                            load,
                             channel.bandwidth(),
-                            edge,
                             routing_configuration,
-                            processed_base_configuration,
-                        ),
-                        RoutingType::SourceChannel => {
-                            // Grab load from core source channels
-                            let load = core
-                                .source_loads()
-                                .as_ref()
-                                .ok_or(missing_source_loads(core.id()))?
-                                .get(direction)
-                                .ok_or(missing_source_load(core.id(), direction))?;
-
-                            // Flip direction. The rendering logic assumes direction from the source
-                            // point of view, not the core's.
-                            let flipped_direction = match direction {
-                                Directions::North => Directions::South,
-                                Directions::South => Directions::North,
-                                Directions::East => Directions::West,
-                                Directions::West => Directions::East,
-                            };
-
-                            TextInformation::source_load(
-                                &flipped_direction,
+                        )?);
+                    } else {
+                        // Generate load text
+                        let link_load_text = match target {
+                            RoutingType::OutputChannel => TextInformation::link_load(
+                                direction,
                                 x,
                                 y,
                                 load,
                                 channel.bandwidth(),
+                                edge,
                                 routing_configuration,
                                 processed_base_configuration,
-                            )
-                        }
-                    };
+                            ),
+                            RoutingType::SourceChannel => {
+                                // Flip direction. The rendering logic assumes direction from the source
+                                // point of view, not the core's.
+                                let flipped_direction = opposite_direction(direction);
+
+                                TextInformation::source_load(
+                                    &flipped_direction,
+                                    x,
+                                    y,
+                                    load,
+                                    channel.bandwidth(),
+                                    routing_configuration,
+                                    processed_base_configuration,
+                                )
+                            }
+                        };
 
-                    // This channel data might need the viewBox extended to be fully displayed.
-                    offsets.update(Offsets::try_from_channel(&link_load_text, direction)?);
-                    // Add the generated text to the result
-                    ret.links_load.push(link_load_text);
+                        // This channel data might need the viewBox extended to be fully displayed.
+                        offsets.update(Offsets::from_channel(&link_load_text, direction));
+                        // Add the generated text to the result
+                        ret.links_load.push(link_load_text);
+                    }
 
-                    // Additional parameter, if any, but only if this is not an edge connection.
-                    if let Some(link_secondary_text) = get_secondary_channel_attribute(
+                    // Additional parameters, if any.
+                    // The load takes the first slot, even when it is rendered as a heatmap.
+                    push_channel_attributes(
+                        &routed_labels,
+                        target,
                         x,
                         y,
                         edge,
-                        &mut configuration.channel_config().iter(),
+                        border_routers,
+                        core,
                         channel,
                         direction,
+                        offsets,
+                        ret,
+                        diagnostics,
                         processed_base_configuration,
-                    ) {
-                        // This channel data might need the viewBox extended to be fully displayed.
-                        offsets.update(Offsets::try_from_channel(&link_secondary_text, direction)?);
-                        ret.links_load.push(link_secondary_text);
-                    }
+                    )?;
                 }
             }
         }
     }
 
-    let edge_directions = match core.matrix_edge() {
-        Some(edge_position) => BTreeSet::from(edge_position),
-        None => BTreeSet::new(),
-    };
-
-    // Render additional parameter(s) if requested for non-routed directions, ignoring
-    // edge connections, if any. Note that direction has type &&Direrctions but get coerced
-    // into &Directions.
-    // See https://web.mit.edu/rust-lang_v1.25/arch/amd64_ubuntu1404/share/doc/rust/html/book/first-edition/deref-coercions.html#deref-and-method-calls
-    for direction in remaining_directions.difference(&edge_directions) {
-        let channel = core
-            .channels()
-            .channel()
-            .get(direction)
-            .ok_or(missing_channel(core.id(), direction))?;
-
-        let mut iter = configuration.channel_config().iter();
-
-        let (x, y, _) = channel_info_details(
-            direction,
-            connections_group,
-            core,
-            &RoutingType::OutputChannel,
-        )?;
-
-        if let Some(channel_attributes) = channel.other_attributes() {
-            // First element
-            if let Some((key, field_configuration)) = iter.next() {
-                match channel_attributes.get(key) {
-                    Some(attribute_value) => {
-                        let link_text = TextInformation::link_primary(
-                            direction,
-                            x,
-                            y,
-                            attribute_value,
-                            // Edge is always false, we are not walking over any edge direction.
-                            // Set difference iterator removes them.
-                            false,
-                            field_configuration,
-                            processed_base_configuration,
-                        );
-                        // This channel data might need the viewBox extended to be fully displayed.
-                        offsets.update(Offsets::try_from_channel(&link_text, direction)?);
-                        ret.links_load.push(link_text);
-                    }
-                    None => {
-                        // Not all attributes must be present on every channel I suppose.
-                        // Do nothing if this channel does not have the requested one.
-                    }
+    // Render additional parameter(s) if requested for non-routed links.
+    // Source links only exist on edge connections. Wrap-around connections are inner connections,
+    // whose attributes are already displayed on their output link.
+    for (target, directions) in [
+        (RoutingType::OutputChannel, remaining_directions),
+        (RoutingType::SourceChannel, remaining_sources),
+    ] {
+        for direction in directions {
+            if let RoutingType::SourceChannel = target {
+                if let ConnectionType::Connection(_) = get_connection_type(
+                    connections_group,
+                    &DirectionType::Out(*direction),
+                    core.id(),
+                )? {
+                    continue;
                 }
             }
-        }
 
-        // Second element, if any.
-        if let Some(link_secondary_text) = get_secondary_channel_attribute(
-            x,
-            y,
-            // Edge is always false, we are not walking over any edge direction.
-            // Set difference iterator removes them.
-            false,
-            &mut iter,
-            channel,
-            direction,
-            processed_base_configuration,
-        ) {
-            // This channel data might need the viewBox extended to be fully displayed.
-            offsets.update(Offsets::try_from_channel(&link_secondary_text, direction)?);
-            ret.links_load.push(link_secondary_text);
+            let channel = core
+                .channels()
+                .channel()
+                .get(direction)
+                .ok_or(missing_channel(core.id(), direction))?;
+
+            let (x, y, edge) = channel_info_details(direction, connections_group, core, &target)?;
+
+            push_channel_attributes(
+                &labels,
+                &target,
+                x,
+                y,
+                edge,
+                border_routers,
+                core,
+                channel,
+                direction,
+                offsets,
+                ret,
+                diagnostics,
+                processed_base_configuration,
+            )?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use manycore_parser::Directions;
+
+    use crate::{
+        tasks_group::MAXIMUM_TASK_FONT_SIZE,
+        tests::{test_configuration, test_system},
+        BaseConfiguration, Offsets, MAXIMUM_ATTRIBUTE_FONT_SIZE, SVG,
+    };
+
+    #[test]
+    fn can_show_channel_attributes_on_edges() {
+        let mut manycore = test_system();
+        let mut configuration = test_configuration("conf8.json");
+
+        let base_configuration =
+            BaseConfiguration::new(MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_TASK_FONT_SIZE);
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &base_configuration)
+            .expect("Could not generate update based on configuration.");
+
+        // Each of the 12 border channels shows its status on both its sink and source links.
+        assert_eq!(
+            update
+                .information_group
+                .matches("class=\"edgeData themeText\">Status: ")
+                .count(),
+            24
+        );
+
+        let border_routers: Vec<Offsets> = svg
+            .root
+            .sinks_sources_group
+            .g()
+            .iter()
+            .map(Offsets::from_sinksource)
+            .collect();
+        let covers_border_router = |label: &Offsets| {
+            border_routers
+                .iter()
+                .any(|border_router| border_router.overlaps(label))
+        };
+
+        for layer in svg.root.information_group.groups_mut().iter() {
+            for label in layer.links_load.iter() {
+                assert!(!covers_border_router(&label.bounding_box()));
+            }
+        }
+
+        // Core 2 East sink label is laid out 177px past the sink start, over its border router.
+        // It is moved towards the core, half a font size at a time, until clear.
+        let core_2 = &svg.root.information_group.groups_mut()[2];
+        let label = core_2
+            .links_load
+            .iter()
+            .find(|label| label.is_edge_data() && *label.direction() == Some(Directions::East))
+            .expect("Missing core 2 East sink label");
+        assert_eq!(*label.x(), 631);
+
+        let placed = label.bounding_box();
+        let laid_out = Offsets::new(
+            placed.left().saturating_add(48),
+            *placed.top(),
+            placed.right().saturating_add(48),
+            *placed.bottom(),
+        );
+        assert!(covers_border_router(&laid_out));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::ColourInterpolation;
+
+/// An sRGB colour. Each channel is in the 0-1 range.
+#[derive(Clone, Copy)]
+struct Rgb {
+    r: f64,
+    g: f64,
+    b: f64,
+}
+
+impl Rgb {
+    /// Parses a hexadecimal colour, either `#rrggbb` or `#rgb`.
+    fn parse(colour: &str) -> Option<Self> {
+        let hex = colour.strip_prefix('#')?;
+        if !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+        let (r, g, b) = match hex.len() {
+            6 => (
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            ),
+            3 => (
+                channel(&hex[0..1].repeat(2))?,
+                channel(&hex[1..2].repeat(2))?,
+                channel(&hex[2..3].repeat(2))?,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            r: f64::from(r) / 255.0,
+            g: f64::from(g) / 255.0,
+            b: f64::from(b) / 255.0,
+        })
+    }
+
+    /// Formats the colour as `#rrggbb`.
+    fn to_hex(self) -> String {
+        let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(self.r),
+            channel(self.g),
+            channel(self.b)
+        )
+    }
+
+    /// Converts to linear-light RGB.
+    fn to_linear(self) -> Self {
+        let decode = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self {
+            r: decode(self.r),
+            g: decode(self.g),
+            b: decode(self.b),
+        }
+    }
+
+    /// Converts from linear-light RGB back to sRGB.
+    fn to_srgb(self) -> Self {
+        let encode = |c: f64| {
+            if c <= 0.0031308 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+
+        Self {
+            r: encode(self.r),
+            g: encode(self.g),
+            b: encode(self.b),
+        }
+    }
+}
+
+/// A colour in the OKLab perceptual colour space.
+/// See https://bottosson.github.io/posts/oklab/
+#[derive(Clone, Copy)]
+struct Oklab {
+    l: f64,
+    a: f64,
+    b: f64,
+}
+
+impl Oklab {
+    /// Converts from linear-light RGB.
+    fn from_linear_rgb(rgb: &Rgb) -> Self {
+        let l = (0.4122214708 * rgb.r + 0.5363325363 * rgb.g + 0.0514459929 * rgb.b).cbrt();
+        let m = (0.2119034982 * rgb.r + 0.6806995451 * rgb.g + 0.1073969566 * rgb.b).cbrt();
+        let s = (0.0883024619 * rgb.r + 0.2817188376 * rgb.g + 0.6299787005 * rgb.b).cbrt();
+
+        Self {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+
+    /// Converts to linear-light RGB.
+    fn to_linear_rgb(self) -> Rgb {
+        let l = (self.l + 0.3963377774 * self.a + 0.2158037573 * self.b).powi(3);
+        let m = (self.l - 0.1055613458 * self.a - 0.0638541728 * self.b).powi(3);
+        let s = (self.l - 0.0894841775 * self.a - 1.2914855480 * self.b).powi(3);
+
+        Rgb {
+            r: 4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            g: -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            b: -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        }
+    }
+}
+
+/// Linear interpolation between two values.
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+/// Interpolates between two hexadecimal colours. `t` is the position between the two, in the 0-1 range.
+/// Returns [`None`] if either colour can't be parsed or if the interpolation mode is discrete.
+pub(crate) fn interpolate_colours(
+    from: &str,
+    to: &str,
+    t: f64,
+    interpolation: &ColourInterpolation,
+) -> Option<String> {
+    let from = Rgb::parse(from)?.to_linear();
+    let to = Rgb::parse(to)?.to_linear();
+    let t = t.clamp(0.0, 1.0);
+
+    let mixed = match interpolation {
+        ColourInterpolation::Discrete => return None,
+        ColourInterpolation::LinearRgb => Rgb {
+            r: lerp(from.r, to.r, t),
+            g: lerp(from.g, to.g, t),
+            b: lerp(from.b, to.b, t),
+        },
+        ColourInterpolation::Oklab => {
+            let from = Oklab::from_linear_rgb(&from);
+            let to = Oklab::from_linear_rgb(&to);
+
+            Oklab {
+                l: lerp(from.l, to.l, t),
+                a: lerp(from.a, to.a, t),
+                b: lerp(from.b, to.b, t),
+            }
+            .to_linear_rgb()
+        }
+    };
+
+    Some(mixed.to_srgb().to_hex())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::interpolate_colours;
+    use crate::ColourInterpolation;
+
+    #[test]
+    fn can_interpolate_midpoint() {
+        // Halfway in linear light is brighter than halfway in sRGB.
+        assert_eq!(
+            interpolate_colours("#000000", "#ffffff", 0.5, &ColourInterpolation::LinearRgb),
+            Some("#bcbcbc".to_string())
+        );
+        // OKLab lightness is perceptually uniform.
+        assert_eq!(
+            interpolate_colours("#000", "#fff", 0.5, &ColourInterpolation::Oklab),
+            Some("#636363".to_string())
+        );
+        // Stops are returned as they are.
+        assert_eq!(
+            interpolate_colours("#22c55e", "#dc2626", 0.0, &ColourInterpolation::Oklab),
+            Some("#22c55e".to_string())
+        );
+    }
+
+    #[test]
+    fn does_not_interpolate_discrete_or_non_hex() {
+        assert_eq!(
+            interpolate_colours("#000000", "#ffffff", 0.5, &ColourInterpolation::Discrete),
+            None
+        );
+        assert_eq!(
+            interpolate_colours("black", "#ffffff", 0.5, &ColourInterpolation::LinearRgb),
+            None
+        );
+        assert_eq!(
+            interpolate_colours("#00000g", "#ffffff", 0.5, &ColourInterpolation::Oklab),
+            None
+        );
+    }
+}
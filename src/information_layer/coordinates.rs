@@ -4,7 +4,7 @@ use manycore_parser::{SystemDimensionsT, COORDINATES_KEY};
 
 use crate::{
     generation_error, CoordinateT, CoordinatesOrientation, FieldConfiguration, InformationLayer,
-    ProcessedBaseConfiguration, SVGError, TextInformation, HALF_SIDE_LENGTH, SIDE_LENGTH,
+    ProcessedBaseConfiguration, SVGError, TextInformation,
 };
 
 /// Generates coordinates text.
@@ -20,8 +20,9 @@ pub(crate) fn make_coordinates(
 ) -> Result<(), SVGError> {
     if let Some(order_config) = core_config.get(COORDINATES_KEY) {
         // Text coordinates
-        let x = core_x + HALF_SIDE_LENGTH;
-        let y = core_y + SIDE_LENGTH;
+        let geometry = processed_base_configuration.geometry();
+        let x = core_x + geometry.half_side_length();
+        let y = core_y + geometry.side_length();
 
         // (X, Y) text repesentation
         let (cx, cy) = match order_config {
@@ -0,0 +1,480 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use manycore_parser::{
+    Core, Directions, ElementIDT, ManycoreSystem, RoutingType, WithID, WithXMLAttributes,
+    COORDINATES_KEY, ID_KEY,
+};
+
+use super::{
+    channel_info_details, channel_labels, colour_interpolation::interpolate_colours,
+    get_connection_type, layout_direction, missing_channel, ChannelLabel, InformationLayer,
+    ProcessingInformation, TextInformation, OFFSET_FROM_BORDER,
+};
+use crate::{
+    check_same_structure, no_processing_group, style::ElementRule, ClipPath, ColourInterpolation,
+    Configuration, ConnectionType, ConnectionsParentGroup, CoordinateT, DirectionType,
+    FieldConfiguration, Offsets, ProcessedBaseConfiguration, ProcessingGroup, SVGError, SVG,
+    USE_FREEFORM_CLIP_PATH,
+};
+
+static DIFF_DECREASE_COLOUR: &'static str = "#2563eb";
+static DIFF_NEUTRAL_COLOUR: &'static str = "#a3a3a3";
+static DIFF_INCREASE_COLOUR: &'static str = "#dc2626";
+
+/// Largest absolute difference of every configured attribute, per element kind.
+/// Used to normalise the diverging colour scale.
+#[derive(Default)]
+pub(crate) struct DeltaRanges {
+    core: BTreeMap<String, f64>,
+    router: BTreeMap<String, f64>,
+    channel: BTreeMap<String, f64>,
+}
+
+/// Numerical difference of an attribute between two elements, if both values are numbers.
+fn attribute_delta<T: WithXMLAttributes>(before: &T, after: &T, key: &str) -> Option<f64> {
+    let parse = |element: &T| {
+        element
+            .other_attributes()
+            .as_ref()?
+            .get(key)?
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+    };
+
+    Some(parse(after)? - parse(before)?)
+}
+
+/// Keys of the configuration whose values can be compared, i.e. text and colour fields.
+fn comparable_keys(
+    configuration: &BTreeMap<String, FieldConfiguration>,
+) -> impl Iterator<Item = (&String, &FieldConfiguration)> {
+    configuration.iter().filter(|(key, field_configuration)| {
+        key.as_str() != ID_KEY
+            && key.as_str() != COORDINATES_KEY
+            && matches!(
+                field_configuration,
+                FieldConfiguration::Text { .. }
+                    | FieldConfiguration::ColouredText { .. }
+                    | FieldConfiguration::Fill { .. }
+            )
+    })
+}
+
+/// Updates the largest absolute difference of every configured attribute of the provided elements.
+fn update_ranges<T: WithXMLAttributes>(
+    ranges: &mut BTreeMap<String, f64>,
+    configuration: &BTreeMap<String, FieldConfiguration>,
+    before: &T,
+    after: &T,
+) {
+    for (key, _) in comparable_keys(configuration) {
+        if let Some(delta) = attribute_delta(before, after, key) {
+            let range = ranges.entry(key.clone()).or_insert(0.0);
+            *range = range.max(delta.abs());
+        }
+    }
+}
+
+impl DeltaRanges {
+    /// Calculates the [`DeltaRanges`] of the configured attributes between two [`ManycoreSystem`]s.
+    /// Systems are expected to have the same structure.
+    pub(crate) fn new(
+        configuration: &Configuration,
+        before: &ManycoreSystem,
+        after: &ManycoreSystem,
+    ) -> Self {
+        let mut ret = DeltaRanges::default();
+
+        for (before, after) in before
+            .cores()
+            .list()
+            .iter()
+            .zip(after.cores().list().iter())
+        {
+            update_ranges(&mut ret.core, configuration.core_config(), before, after);
+            update_ranges(
+                &mut ret.router,
+                configuration.router_config(),
+                before.router(),
+                after.router(),
+            );
+
+            for (direction, after_channel) in after.channels().channel() {
+                if let Some(before_channel) = before.channels().channel().get(direction) {
+                    update_ranges(
+                        &mut ret.channel,
+                        configuration.channel_config(),
+                        before_channel,
+                        after_channel,
+                    );
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+/// Calculates the colour of a difference on the diverging scale.
+/// Decreases are blue, increases red and unchanged values grey.
+fn diverging_colour(delta: f64, range: Option<&f64>) -> String {
+    let t = match range {
+        Some(range) if *range > 0.0 => (delta / range).clamp(-1.0, 1.0),
+        _ => 0.0,
+    };
+    let to = if t < 0.0 {
+        DIFF_DECREASE_COLOUR
+    } else {
+        DIFF_INCREASE_COLOUR
+    };
+
+    interpolate_colours(
+        DIFF_NEUTRAL_COLOUR,
+        to,
+        t.abs(),
+        &ColourInterpolation::Oklab,
+    )
+    .unwrap_or_else(|| DIFF_NEUTRAL_COLOUR.to_string())
+}
+
+/// Formats a difference with an explicit sign, e.g. +3 or -0.5.
+fn delta_string(delta: f64) -> String {
+    format!("{:+}", delta)
+}
+
+/// Generates the difference [`InformationLayer`] content for a [`WithID`] element.
+/// Text fields show the signed difference, fill fields colour the element.
+fn generate_with_id_diff<T: WithID<ElementIDT> + WithXMLAttributes>(
+    base_x: CoordinateT,
+    mut base_y: CoordinateT,
+    configuration: &BTreeMap<String, FieldConfiguration>,
+    before: &T,
+    after: &T,
+    variant: &str,
+    ranges: &BTreeMap<String, f64>,
+    group: &mut ProcessingInformation,
+    element_rules: &mut Vec<ElementRule>,
+    processed_base_configuration: &ProcessedBaseConfiguration,
+) {
+    let base_x = base_x.saturating_add(OFFSET_FROM_BORDER);
+
+    for (key, field_configuration) in comparable_keys(configuration) {
+        // Only numerical values have a difference.
+        let delta = match attribute_delta(before, after, key) {
+            Some(delta) => delta,
+            None => continue,
+        };
+        let colour = diverging_colour(delta, ranges.get(key));
+
+        match field_configuration {
+            FieldConfiguration::Fill { .. } => {
+                element_rules.push(ElementRule::new(
+                    format!("{}{}", variant, after.id()),
+                    vec![("fill", colour)],
+                ));
+            }
+            FieldConfiguration::Text { display, .. }
+            | FieldConfiguration::ColouredText { display, .. } => {
+                group.information.push(TextInformation::new(
+                    base_x,
+                    base_y,
+                    *processed_base_configuration.attribute_font_size(),
+                    "start",
+                    "text-before-edge",
+                    Some(&colour),
+                    None,
+                    format!("{}: {}", display, delta_string(delta)),
+                ));
+
+                base_y = base_y
+                    .saturating_add(*processed_base_configuration.attribute_font_size_coordinate());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl InformationLayer {
+    /// Generates a new [`InformationLayer`] showing the difference of the configured attributes
+    /// between two versions of the same core.
+    pub(crate) fn diff(
+        configuration: &Configuration,
+        before: &Core,
+        after: &Core,
+        ranges: &DeltaRanges,
+        element_rules: &mut Vec<ElementRule>,
+        processing_group: &ProcessingGroup,
+        connections_group: &ConnectionsParentGroup,
+        offsets: &mut Offsets,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> Result<Self, SVGError> {
+        let mut ret = InformationLayer::default();
+        ret.clip_path = USE_FREEFORM_CLIP_PATH;
+        ret.core_id = *after.id();
+
+        // Core
+        let (core_x, core_y) = processing_group.core().move_coordinates();
+        generate_with_id_diff(
+            *core_x,
+            *core_y,
+            configuration.core_config(),
+            before,
+            after,
+            "c",
+            &ranges.core,
+            &mut ret.core_group,
+            element_rules,
+            processed_base_configuration,
+        );
+        ret.core_group.clip_path = format!("url(#{})", ClipPath::make_core_id(after.id()));
+
+        // Router
+        let (router_x, router_y) = processing_group.router().move_coordinates();
+        generate_with_id_diff(
+            *router_x,
+            router_y - processed_base_configuration.geometry().router_offset(),
+            configuration.router_config(),
+            before.router(),
+            after.router(),
+            "r",
+            &ranges.router,
+            &mut ret.router_group,
+            element_rules,
+            processed_base_configuration,
+        );
+        ret.router_group.clip_path =
+            format!("url(#{})", ClipPath::make_router_id(after.router().id()));
+
+        // Channels. Edge connections are skipped along with border routers, differences are only shown on inner links.
+        // Channel fields are stacked next to the link in the requested order, like in the regular layer.
+        let labels = channel_labels(
+            configuration,
+            comparable_keys(configuration.channel_config()).filter(|(_, field_configuration)| {
+                !matches!(field_configuration, FieldConfiguration::Fill { .. })
+            }),
+            0,
+        );
+        let directions: BTreeSet<&Directions> = after.channels().channel().keys().collect();
+        for direction in directions {
+            if let ConnectionType::EdgeConnection(_) = get_connection_type(
+                connections_group,
+                &DirectionType::Out(*direction),
+                after.id(),
+            )? {
+                continue;
+            }
+
+            let after_channel = after
+                .channels()
+                .channel()
+                .get(direction)
+                .ok_or(missing_channel(after.id(), direction))?;
+            let before_channel = before
+                .channels()
+                .channel()
+                .get(direction)
+                .ok_or(missing_channel(before.id(), direction))?;
+
+            let (x, y, _) = channel_info_details(
+                direction,
+                connections_group,
+                after,
+                &RoutingType::OutputChannel,
+            )?;
+
+            for ChannelLabel {
+                key,
+                field_configuration,
+                side,
+                slot,
+            } in labels.iter()
+            {
+                let delta = match attribute_delta(before_channel, after_channel, key) {
+                    Some(delta) => delta,
+                    None => continue,
+                };
+
+                let display = match field_configuration {
+                    FieldConfiguration::Text { display, .. }
+                    | FieldConfiguration::ColouredText { display, .. } => display.clone(),
+                    _ => continue,
+                };
+                let text_configuration = FieldConfiguration::Text {
+                    display,
+                    colour: Some(diverging_colour(delta, ranges.channel.get(*key))),
+                };
+                let data = delta_string(delta);
+                // Text fields don't record diagnostics.
+                let mut diagnostics = Vec::new();
+
+                let link_text = TextInformation::link_attribute(
+                    direction,
+                    x,
+                    y,
+                    after.id(),
+                    key,
+                    &data,
+                    false,
+                    side,
+                    *slot,
+                    &text_configuration,
+                    &mut diagnostics,
+                    processed_base_configuration,
+                );
+
+                // This channel data might need the viewBox extended to be fully displayed.
+                offsets.update(Offsets::from_channel(
+                    &link_text,
+                    &layout_direction(direction, side),
+                ));
+                ret.links_load.push(link_text);
+            }
+        }
+
+        Ok(ret)
+    }
+}
+
+impl SVG {
+    /// Generates an [`SVG`] showing the difference between two runs of the same system.
+    /// Configured core, router and channel attributes show their numerical change, coloured on a diverging scale.
+    /// Tasks allocated to a different core than in `before` are marked.
+    /// Border routers are hidden, so channels on edge links are not compared, as in a regular [`SVG`]
+    /// without border routers. Channel loads are not compared either: systems are not routed.
+    pub fn diff(
+        before: &ManycoreSystem,
+        after: &ManycoreSystem,
+        configuration: &Configuration,
+    ) -> Result<SVG, SVGError> {
+        check_same_structure(before, after)?;
+
+        let before_cores = before.cores().list();
+        let after_cores = after.cores().list();
+
+        let mut svg: SVG = after.try_into()?;
+        let ranges = DeltaRanges::new(configuration, before, after);
+        let mut offsets = Offsets::default();
+
+        for (i, (before_core, after_core)) in
+            before_cores.iter().zip(after_cores.iter()).enumerate()
+        {
+            let processing_group = svg
+                .root
+                .processing_group
+                .g()
+                .get(i)
+                .ok_or(no_processing_group(i))?;
+
+            svg.root
+                .information_group
+                .groups_mut()
+                .push(InformationLayer::diff(
+                    configuration,
+                    before_core,
+                    after_core,
+                    &ranges,
+                    svg.style.element_rules_mut(),
+                    processing_group,
+                    &svg.root.connections_group,
+                    &mut offsets,
+                    &svg.processed_base_configuration,
+                )?);
+
+            if before_core.allocated_task() != after_core.allocated_task() {
+                if let Some(task_id) = after_core.allocated_task() {
+                    svg.root.tasks_group.mark_changed(task_id)?;
+                }
+            }
+        }
+
+        // Border routers are hidden in a diff and diffs don't report diagnostics.
+        if *configuration.label_placement() {
+            offsets = svg.place_labels(false, &mut Vec::new());
+        }
+
+        svg.root.tasks_group.into_iter().for_each(|t| {
+            offsets.update(Offsets::from_task(t));
+        });
+        svg.view_box.fit_offsets(&offsets);
+
+        Ok(svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{assert_mismatch, test_configuration, test_system, test_system_file},
+        SVG,
+    };
+
+    #[test]
+    fn can_diff_systems() {
+        let configuration = test_configuration("conf3.json");
+
+        let svg = SVG::diff(&test_system(), &test_system(), &configuration)
+            .expect("Could not diff systems.");
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        // Nothing changed, every difference is zero and grey.
+        assert!(res.contains("fill=\"#a3a3a3\">Temp: +0<"));
+        assert!(!res.contains("Temp: -"));
+        assert!(res.contains("\n#c0 {fill: #a3a3a3;}"));
+        assert!(!res.contains("stroke=\"#f59e0b\""));
+    }
+
+    #[test]
+    fn can_diff_changed_systems() {
+        let configuration = test_configuration("conf3.json");
+
+        // Core 0 gets younger and hotter, Task 4 moves from core 5 to core 8.
+        let svg = SVG::diff(
+            &test_system(),
+            &test_system_file("VisualiserOutput2.xml"),
+            &configuration,
+        )
+        .expect("Could not diff systems.");
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        // Increases are red, decreases blue. Both are the largest change, hence the full colour.
+        assert!(res.contains("fill=\"#dc2626\">Temp: +15<"));
+        assert!(res.contains("\n#c0 {fill: #2563eb;}"));
+        assert!(res.contains("\n#c1 {fill: #a3a3a3;}"));
+        // Only the moved task is marked.
+        assert_eq!(res.matches("stroke=\"#f59e0b\"").count(), 1);
+    }
+
+    #[test]
+    fn skips_edge_channels_and_loads() {
+        // Routing and border routers are configured, but ignored.
+        let configuration = test_configuration("conf3.json");
+
+        let svg = SVG::diff(&test_system(), &test_system(), &configuration)
+            .expect("Could not diff systems.");
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        // A 3x3 mesh has 24 inner output links, the 12 edge ones are left out.
+        assert_eq!(res.matches(">Age: +0<").count(), 24);
+        assert!(!res.contains("%<"));
+    }
+
+    #[test]
+    fn cannot_diff_different_systems() {
+        let configuration = test_configuration("conf3.json");
+
+        // 3x3 and 2x2 systems.
+        assert_mismatch(SVG::diff(
+            &test_system(),
+            &test_system_file("VisualiserOutput3.xml"),
+            &configuration,
+        ));
+        // Core 4 has no North channel.
+        assert_mismatch(SVG::diff(
+            &test_system(),
+            &test_system_file("VisualiserOutput4.xml"),
+            &configuration,
+        ));
+    }
+}
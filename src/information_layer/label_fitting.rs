@@ -0,0 +1,250 @@
+use crate::{
+    ClipPath, CoordinateT, Diagnostic, FontSizeT, LabelOverflow, ProcessedBaseConfiguration,
+    TextInformation, MINIMUM_ATTRIBUTE_FONT_SIZE,
+};
+
+use super::ProcessingInformation;
+
+static ELLIPSIS: char = '…';
+/// How much a label is shrunk at a time, when trying to fit it.
+static SHRINK_STEP: FontSizeT = 1.0;
+
+/// What happened to a label that does not fit its element.
+enum Overflow {
+    Clipped,
+    Shrunk,
+    ShrunkAndClipped,
+    Truncated,
+    Omitted,
+}
+
+impl Overflow {
+    /// Describes the overflow, according to the [`LabelOverflow`] policy in use.
+    fn reason(&self, policy: &LabelOverflow) -> &'static str {
+        match (self, policy) {
+            (Overflow::Clipped, _) => "Label does not fit its element and is cut off.",
+            (Overflow::Shrunk, _) => "Label does not fit its element, it was shrunk.",
+            (Overflow::ShrunkAndClipped, _) => {
+                "Label does not fit its element even at the minimum font size and is cut off."
+            }
+            (Overflow::Truncated, LabelOverflow::Tooltip) => {
+                "Label does not fit its element, it was truncated and is shown in full on hover."
+            }
+            (Overflow::Truncated, _) => "Label does not fit its element, it was truncated.",
+            (Overflow::Omitted, LabelOverflow::Tooltip) => {
+                "Label does not fit its element, it is only shown on hover."
+            }
+            (Overflow::Omitted, _) => "Label does not fit its element, it was left out.",
+        }
+    }
+}
+
+/// Stacks the lines of a core or router label inside its [`ClipPath`], one below the other.
+/// Lines that don't fit are handled according to the [`LabelOverflow`] policy and reported.
+pub(crate) struct LabelStack<'a> {
+    x: CoordinateT,
+    y: CoordinateT,
+    text_anchor: &'static str,
+    font_size: FontSizeT,
+    policy: LabelOverflow,
+    clip_path: &'a ClipPath,
+    /// The element the label belongs to, e.g. `c3` for core 3.
+    element: String,
+    /// Full text of the lines that were truncated or omitted, for the tooltip.
+    hidden: Vec<String>,
+}
+
+impl<'a> LabelStack<'a> {
+    /// Generates a new [`LabelStack`] whose first line starts at `x` and `y`.
+    pub(crate) fn new(
+        x: CoordinateT,
+        y: CoordinateT,
+        text_anchor: &'static str,
+        clip_path: &'a ClipPath,
+        element: String,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> Self {
+        Self {
+            x,
+            y,
+            text_anchor,
+            font_size: *processed_base_configuration.attribute_font_size(),
+            policy: *processed_base_configuration.label_overflow(),
+            clip_path,
+            element,
+            hidden: Vec::new(),
+        }
+    }
+
+    /// Calculates the width available to the next line, if it can be placed at all.
+    fn room(&self, font_size: FontSizeT) -> Option<CoordinateT> {
+        let bottom = self.y.saturating_add(font_size.round() as CoordinateT);
+        let (left, right) = self.clip_path.horizontal_span(self.y, bottom)?;
+
+        (left <= self.x).then(|| right.saturating_sub(self.x))
+    }
+
+    /// Whether `text` fits as the next line at the given `font_size`.
+    fn fits(&self, text: &str, font_size: FontSizeT) -> bool {
+        self.room(font_size).is_some_and(|room| {
+            TextInformation::calculate_length_util(font_size, text, None) <= room
+        })
+    }
+
+    /// Shortens `text` with an ellipsis until it fits in `room`.
+    fn truncate(&self, text: &str, room: CoordinateT) -> String {
+        let mut truncated = text.to_string();
+
+        while truncated.pop().is_some() {
+            truncated.push(ELLIPSIS);
+            if TextInformation::calculate_length_util(self.font_size, &truncated, None) <= room {
+                return truncated;
+            }
+            truncated.pop();
+        }
+
+        ELLIPSIS.to_string()
+    }
+
+    /// Adds a [`TextInformation`] line to `group` and moves below it.
+    fn push_line(
+        &mut self,
+        group: &mut ProcessingInformation,
+        fill: Option<&String>,
+        text: String,
+        font_size: FontSizeT,
+    ) {
+        group.information.push(TextInformation::new(
+            self.x,
+            self.y,
+            font_size,
+            self.text_anchor,
+            "text-before-edge",
+            fill,
+            None,
+            text,
+        ));
+
+        self.y = self.y.saturating_add(font_size.round() as CoordinateT);
+    }
+
+    /// Adds the line for attribute `key` to `group`, fitting it according to the [`LabelOverflow`] policy.
+    pub(crate) fn push(
+        &mut self,
+        group: &mut ProcessingInformation,
+        key: &str,
+        fill: Option<&String>,
+        text: String,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        if self.fits(&text, self.font_size) {
+            self.push_line(group, fill, text, self.font_size);
+            return;
+        }
+
+        let overflow = match self.policy {
+            LabelOverflow::Clip => {
+                self.push_line(group, fill, text.clone(), self.font_size);
+
+                Overflow::Clipped
+            }
+            LabelOverflow::Shrink => {
+                let mut font_size = self.font_size;
+                while font_size > MINIMUM_ATTRIBUTE_FONT_SIZE && !self.fits(&text, font_size) {
+                    font_size = (font_size - SHRINK_STEP).max(MINIMUM_ATTRIBUTE_FONT_SIZE);
+                }
+
+                let overflow = if self.fits(&text, font_size) {
+                    Overflow::Shrunk
+                } else {
+                    Overflow::ShrunkAndClipped
+                };
+                self.push_line(group, fill, text.clone(), font_size);
+
+                overflow
+            }
+            LabelOverflow::Ellipsis | LabelOverflow::Tooltip => {
+                // A tooltip needs something to hover: the first line is kept even with no room.
+                let room = match (self.room(self.font_size), self.policy) {
+                    (None, LabelOverflow::Tooltip) if group.information.is_empty() => Some(0),
+                    (room, _) => room,
+                };
+
+                let overflow = match room {
+                    Some(room) => {
+                        let truncated = self.truncate(&text, room);
+                        self.push_line(group, fill, truncated, self.font_size);
+
+                        Overflow::Truncated
+                    }
+                    None => Overflow::Omitted,
+                };
+
+                if let LabelOverflow::Tooltip = self.policy {
+                    self.hidden.push(text.clone());
+                }
+
+                overflow
+            }
+        };
+
+        diagnostics.push(Diagnostic::overflowing_label(
+            self.element.clone(),
+            key,
+            &text,
+            overflow.reason(&self.policy),
+        ));
+    }
+
+    /// Shows the full text of truncated and omitted lines when hovering `group`, if any.
+    pub(crate) fn finish(self, group: &mut ProcessingInformation) {
+        if !self.hidden.is_empty() {
+            group.title = Some(self.hidden.join("\n"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ClipPath, LabelOverflow};
+
+    use super::{LabelStack, ProcessingInformation};
+
+    #[test]
+    fn keeps_tooltip_hover_target() {
+        // No vertices, so there is no room for any line.
+        let clip_path = ClipPath::new(String::new());
+        let mut stack = LabelStack {
+            x: 0,
+            y: 0,
+            text_anchor: "start",
+            font_size: 16.0,
+            policy: LabelOverflow::Tooltip,
+            clip_path: &clip_path,
+            element: String::from("c0"),
+            hidden: Vec::new(),
+        };
+
+        let mut group = ProcessingInformation::default();
+        let mut diagnostics = Vec::new();
+        stack.push(
+            &mut group,
+            "@temperature",
+            None,
+            String::from("Temperature: 30"),
+            &mut diagnostics,
+        );
+        stack.push(
+            &mut group,
+            "@load",
+            None,
+            String::from("Load: 12"),
+            &mut diagnostics,
+        );
+        stack.finish(&mut group);
+
+        assert_eq!(group.information.len(), 1);
+        assert_eq!(group.title.as_deref(), Some("Temperature: 30\nLoad: 12"));
+        assert_eq!(diagnostics.len(), 2);
+    }
+}
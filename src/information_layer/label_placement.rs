@@ -0,0 +1,323 @@
+use manycore_parser::Directions;
+use serde::Serialize;
+
+use crate::{
+    CoordinateT, Diagnostic, FontSizeT, Offsets, TextInformation, MINIMUM_ATTRIBUTE_FONT_SIZE, SVG,
+};
+
+use super::utils;
+
+/// How many half font sizes a label can be nudged along its link, in either direction.
+static MAX_NUDGE_STEPS: CoordinateT = 4;
+/// How many font sizes a label can be moved away from its link, when given a leader line.
+static MAX_LEADER_STEPS: CoordinateT = 5;
+/// Size a label is shrunk to, relative to its original one, if it can't be nudged in place.
+static SHRINK_RATIO: FontSizeT = 0.75;
+static LEADER_LINE_STROKE_WIDTH: &'static str = "1";
+
+/// Object representation of an SVG `<line>` connecting a displaced label to its link.
+#[derive(Serialize)]
+pub(crate) struct LeaderLine {
+    #[serde(rename = "@x1")]
+    x1: CoordinateT,
+    #[serde(rename = "@y1")]
+    y1: CoordinateT,
+    #[serde(rename = "@x2")]
+    x2: CoordinateT,
+    #[serde(rename = "@y2")]
+    y2: CoordinateT,
+    #[serde(rename = "@stroke")]
+    stroke: String,
+    #[serde(rename = "@stroke-width")]
+    stroke_width: &'static str,
+    #[serde(rename = "@class", skip_serializing_if = "Option::is_none")]
+    class: Option<&'static str>,
+}
+
+impl LeaderLine {
+    /// Generates a [`LeaderLine`] from a label's original anchor to its current one.
+    fn new(from: (CoordinateT, CoordinateT), label: &TextInformation) -> Self {
+        Self {
+            x1: from.0,
+            y1: from.1,
+            x2: *label.x(),
+            y2: *label.y(),
+            stroke: label.fill().clone(),
+            stroke_width: LEADER_LINE_STROKE_WIDTH,
+            class: *label.class(),
+        }
+    }
+}
+
+/// Result of trying to place a label.
+enum Placement {
+    /// The label is clear of everything else, close to its link.
+    Placed,
+    /// The label had to be moved away from its link, starting from the provided anchor.
+    Displaced((CoordinateT, CoordinateT)),
+    /// The label overlaps something wherever it goes. It was left where it was.
+    Unplaced,
+}
+
+/// Unit vectors along a label's link and away from it, according to the direction the label is laid out for.
+fn axes(direction: &Directions) -> ((CoordinateT, CoordinateT), (CoordinateT, CoordinateT)) {
+    match direction {
+        // Text sits to the right of northbound links and to the left of southbound ones.
+        Directions::North => ((0, 1), (1, 0)),
+        Directions::South => ((0, 1), (-1, 0)),
+        // Text sits above eastbound links and below westbound ones.
+        Directions::East => ((1, 0), (0, -1)),
+        Directions::West => ((1, 0), (0, 1)),
+    }
+}
+
+/// Moves `label` until it does not overlap any of the `placed` boxes.
+/// Nudging along the link is tried first, then shrinking the label, then moving it away from the link.
+fn place(label: &mut TextInformation, direction: &Directions, placed: &[Offsets]) -> Placement {
+    let is_clear =
+        |label: &TextInformation| !placed.iter().any(|b| b.overlaps(&label.bounding_box()));
+
+    let anchor = (*label.x(), *label.y());
+    let font_size = *label.font_size().px();
+    let shrunk_font_size = (font_size * SHRINK_RATIO).max(MINIMUM_ATTRIBUTE_FONT_SIZE);
+    let ((along_x, along_y), (away_x, away_y)) = axes(direction);
+
+    let mut sizes = vec![font_size];
+    if shrunk_font_size < font_size {
+        sizes.push(shrunk_font_size);
+    }
+
+    for size in sizes.iter() {
+        label.set_font_size(*size);
+        let step = (size / 2.0).round() as CoordinateT;
+
+        // 0, 1, -1, 2, -2, ...
+        for nudge in (0..=MAX_NUDGE_STEPS.saturating_mul(2)).map(|i| {
+            if i % 2 == 0 {
+                -i / 2
+            } else {
+                (i + 1) / 2
+            }
+        }) {
+            label.set_x(anchor.0.saturating_add(along_x * nudge * step));
+            label.set_y(anchor.1.saturating_add(along_y * nudge * step));
+
+            if is_clear(label) {
+                return Placement::Placed;
+            }
+        }
+    }
+
+    // Leader lines only help if the label moves clear of its link, so start a couple of steps away.
+    if let Some(size) = sizes.last() {
+        label.set_font_size(*size);
+        let step = size.round() as CoordinateT;
+
+        for distance in 2..=MAX_LEADER_STEPS {
+            label.set_x(anchor.0.saturating_add(away_x * distance * step));
+            label.set_y(anchor.1.saturating_add(away_y * distance * step));
+
+            if is_clear(label) {
+                return Placement::Displaced(anchor);
+            }
+        }
+    }
+
+    label.set_font_size(font_size);
+    label.set_x(anchor.0);
+    label.set_y(anchor.1);
+
+    Placement::Unplaced
+}
+
+impl SVG {
+    /// Moves channel labels so that they don't overlap each other or border routers.
+    /// Labels that can't be placed are reported in `diagnostics`.
+    /// Returns the viewBox [`Offsets`] required by the channel labels, wherever they ended up.
+    pub(crate) fn place_labels(
+        &mut self,
+        show_border_routers: bool,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Offsets {
+        // Border routers and their link labels are hidden with the edgeData class.
+        let mut placed: Vec<Offsets> = if show_border_routers {
+            self.root
+                .sinks_sources_group
+                .g()
+                .iter()
+                .map(Offsets::from_sinksource)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut offsets = Offsets::default();
+
+        for layer in self.root.information_group.groups_mut().iter_mut() {
+            for label in layer.links_load.iter_mut() {
+                let (direction, channel_direction) =
+                    match (label.direction(), label.channel_direction()) {
+                        (Some(direction), Some(channel_direction)) => {
+                            (*direction, channel_direction)
+                        }
+                        _ => continue,
+                    };
+
+                // Hidden labels are neither placed nor displayed.
+                if !show_border_routers && label.is_edge_data() {
+                    continue;
+                }
+
+                let layout_direction = utils::layout_direction(&direction, label.side());
+                match place(label, &layout_direction, &placed) {
+                    Placement::Placed => {}
+                    Placement::Displaced(anchor) => {
+                        layer.leader_lines.push(LeaderLine::new(anchor, label))
+                    }
+                    Placement::Unplaced => diagnostics.push(Diagnostic::unplaced_label(
+                        utils::channel_element(&layer.core_id, &channel_direction),
+                        label.value(),
+                    )),
+                }
+
+                placed.push(label.bounding_box());
+                // This channel data might need the viewBox extended to be fully displayed.
+                offsets.update(Offsets::from_channel(label, &layout_direction));
+            }
+        }
+
+        offsets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use manycore_parser::Directions;
+
+    use crate::{
+        tasks_group::MAXIMUM_TASK_FONT_SIZE,
+        tests::{test_configuration, test_system},
+        BaseConfiguration, Offsets, TextInformation, MAXIMUM_ATTRIBUTE_FONT_SIZE, SVG,
+    };
+
+    use super::{place, Placement};
+
+    /// A 20px label anchored at the origin, as laid out for an eastbound link.
+    fn label() -> TextInformation {
+        TextInformation::new(
+            0,
+            0,
+            20.0,
+            "start",
+            "central",
+            None,
+            None,
+            String::from("ab"),
+        )
+    }
+
+    fn position(label: &TextInformation) -> (i32, i32, f32) {
+        (*label.x(), *label.y(), *label.font_size().px())
+    }
+
+    #[test]
+    fn can_nudge_label() {
+        let mut label = label();
+
+        // Blocks the label where it is, the first nudge along the link is half a font size to the right.
+        let placement = place(
+            &mut label,
+            &Directions::East,
+            &[Offsets::new(-100, -30, 5, 30)],
+        );
+
+        assert!(matches!(placement, Placement::Placed));
+        assert_eq!(position(&label), (10, 0, 20.0));
+    }
+
+    #[test]
+    fn can_shrink_label() {
+        let mut label = label();
+
+        // Blocks the top of the label anywhere along the link, the shrunk label is short enough.
+        let placement = place(
+            &mut label,
+            &Directions::East,
+            &[Offsets::new(-1000, -100, 1000, -8)],
+        );
+
+        assert!(matches!(placement, Placement::Placed));
+        assert_eq!(position(&label), (0, 0, 15.0));
+    }
+
+    #[test]
+    fn can_displace_label() {
+        let mut label = label();
+
+        // Blocks the whole link, eastbound labels move up by whole (shrunk) font sizes.
+        let placement = place(
+            &mut label,
+            &Directions::East,
+            &[Offsets::new(-1000, -25, 1000, 25)],
+        );
+
+        assert!(matches!(placement, Placement::Displaced((0, 0))));
+        assert_eq!(position(&label), (0, -45, 15.0));
+    }
+
+    #[test]
+    fn leaves_unplaceable_label() {
+        let mut label = label();
+
+        let placement = place(
+            &mut label,
+            &Directions::East,
+            &[Offsets::new(-1000, -1000, 1000, 1000)],
+        );
+
+        assert!(matches!(placement, Placement::Unplaced));
+        assert_eq!(position(&label), (0, 0, 20.0));
+    }
+
+    #[test]
+    fn can_place_labels() {
+        let mut manycore = test_system();
+        let mut configuration = test_configuration("conf12.json");
+
+        let base_configuration =
+            BaseConfiguration::new(MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_TASK_FONT_SIZE);
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &base_configuration)
+            .expect("Could not generate update based on configuration.");
+
+        assert!(*configuration.label_placement());
+
+        // Labels still overlapping an earlier one are exactly those reported.
+        // Border routers are hidden, so are their links' labels.
+        let mut placed: Vec<Offsets> = Vec::new();
+        let mut overlapping = Vec::new();
+        for layer in svg.root.information_group.groups_mut().iter() {
+            for label in layer
+                .links_load
+                .iter()
+                .filter(|label| !label.is_edge_data())
+            {
+                let bounding_box = label.bounding_box();
+                if placed.iter().any(|other| other.overlaps(&bounding_box)) {
+                    overlapping.push(label.value().clone());
+                }
+                placed.push(bounding_box);
+            }
+        }
+        let unplaced: Vec<String> = update
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.attribute() == "label")
+            .map(|diagnostic| diagnostic.value().clone())
+            .collect();
+
+        assert_eq!(overlapping, unplaced);
+    }
+}
@@ -1,30 +1,20 @@
-use std::ops::Div;
-
+use const_format::concatcp;
 use getset::{Getters, Setters};
-use manycore_parser::Directions;
+use manycore_parser::{Directions, ElementIDT};
 use serde::Serialize;
 
 use crate::{
-    sinks_sources_layer::{
-        EAST_SINKS_SOURCES_CONNECTION_DELTA, NORTH_SINKS_SOURCES_CONNECTION_DELTA,
-        SOUTH_SINKS_SOURCES_CONNECTION_DELTA, WEST_SINKS_SOURCES_CONNECTION_DELTA,
-    },
+    serialise_options::precise_decimal,
     style::EDGE_DATA_CLASS_NAME,
-    CoordinateT, FieldConfiguration, FontSizeT, LoadConfiguration, ProcessedBaseConfiguration,
-    RoutingConfiguration, SVGError, CONNECTION_LENGTH, MARKER_HEIGHT,
+    text_metrics::{char_width, text_width},
+    CoordinateT, Diagnostic, FieldConfiguration, FontSizeT, LinkSide, LoadConfiguration, Offsets,
+    ProcessedBaseConfiguration, RoutingConfiguration, LIGHT_COLOURS, TEXT_CLASS_NAME,
 };
 
 use super::utils;
 
 static HORIZONTAL_OFFSET_FROM_LINK: CoordinateT = 5;
 static VERTICAL_OFFSET_FROM_LINK: CoordinateT = 5;
-static HALF_CONNECTION_LENGTH: CoordinateT = CONNECTION_LENGTH
-    .saturating_add(MARKER_HEIGHT)
-    .saturating_div(2);
-static THREE_QUARTERS_CONNECTION_LENGTH: CoordinateT = CONNECTION_LENGTH
-    .saturating_add(MARKER_HEIGHT)
-    .saturating_div(4)
-    .saturating_mul(3);
 pub(crate) static CHAR_V_PADDING: CoordinateT = 6;
 pub(crate) static HALF_CHAR_V_PADDING: CoordinateT = CHAR_V_PADDING.saturating_div(2);
 pub(crate) static CHAR_H_PADDING: FontSizeT = 2.0;
@@ -33,7 +23,8 @@ pub(crate) const DEFAULT_ATTRIBUTE_FONT_SIZE: FontSizeT = 16.0;
 pub(crate) static MINIMUM_ATTRIBUTE_FONT_SIZE: FontSizeT = 10.0;
 pub(crate) static MAXIMUM_ATTRIBUTE_FONT_SIZE: FontSizeT = 24.0;
 
-static ROBOTO_RATIO: FontSizeT = 1.665;
+const EDGE_DATA_TEXT_CLASS_NAME: &'static str =
+    concatcp!(EDGE_DATA_CLASS_NAME, " ", TEXT_CLASS_NAME);
 
 /// Wrapper around font size
 #[derive(Getters)]
@@ -47,9 +38,13 @@ impl Serialize for FontSize {
     where
         S: serde::Serializer,
     {
-        // This conversion will truncate the font size as an f32 does not fit in a u32.
-        // However, values that big should not be provided.
-        serializer.serialize_str(format!("{}px", self.px as u32).as_str())
+        let px = precise_decimal(f64::from(self.px)).unwrap_or_else(|| {
+            // This conversion will truncate the font size as an f32 does not fit in a u32.
+            // However, values that big should not be provided.
+            (self.px as u32).to_string()
+        });
+
+        serializer.serialize_str(format!("{}px", px).as_str())
     }
 }
 
@@ -74,34 +69,93 @@ pub(crate) struct TextInformation {
     #[serde(rename = "@dominant-baseline")]
     dominant_baseline: &'static str,
     #[serde(rename = "@fill")]
+    #[getset(get = "pub")]
     fill: String,
     #[serde(rename = "@class", skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
     class: Option<&'static str>,
     #[serde(rename = "$text")]
+    #[getset(get = "pub")]
     value: String,
+    /// The direction of the channel this text describes, if any.
+    #[serde(skip)]
+    #[getset(get = "pub")]
+    direction: Option<Directions>,
+    /// The side of its channel's link this text is on.
+    #[serde(skip)]
+    #[getset(get = "pub")]
+    side: LinkSide,
+    /// Whether this text describes a source channel, in which case `direction` is flipped.
+    #[serde(skip)]
+    source: bool,
 }
 
 impl TextInformation {
-    /// Calculates the approximate length in pixels of a `<text>` element.
+    /// Calculates the length in pixels of a `<text>` element containing `text`.
+    /// `pad` is expressed in characters.
     pub(crate) fn calculate_length_util(
         font_size: FontSizeT,
-        length: usize,
+        text: &str,
         pad: Option<FontSizeT>,
-    ) -> Result<CoordinateT, SVGError> {
-        let char_width = font_size.div(ROBOTO_RATIO);
-
-        Ok((char_width * u16::try_from(length)? as FontSizeT
+    ) -> CoordinateT {
+        (text_width(text, font_size)
             + if let Some(pad) = pad {
-                char_width * pad
+                char_width(font_size) * pad
             } else {
                 0.0
             })
-        .round() as CoordinateT)
+        .round() as CoordinateT
+    }
+
+    /// Calculates the length in pixels of a [`TextInformation`] instance.
+    pub(crate) fn calculate_length(&self, pad: Option<FontSizeT>) -> CoordinateT {
+        TextInformation::calculate_length_util(self.font_size.px, &self.value, pad)
+    }
+
+    /// Calculates the box a [`TextInformation`] instance covers, according to its anchor and baseline.
+    pub(crate) fn bounding_box(&self) -> Offsets {
+        let width = self.calculate_length(None);
+        let height = self.font_size.px.round() as CoordinateT;
+
+        let left = match self.text_anchor {
+            "middle" => self.x.saturating_sub(width.saturating_div(2)),
+            "end" => self.x.saturating_sub(width),
+            _ => self.x,
+        };
+        let top = match self.dominant_baseline {
+            "text-after-edge" => self.y.saturating_sub(height),
+            "text-before-edge" => self.y,
+            _ => self.y.saturating_sub(height.saturating_div(2)),
+        };
+
+        Offsets::new(
+            left,
+            top,
+            left.saturating_add(width),
+            top.saturating_add(height),
+        )
+    }
+
+    /// Whether this [`TextInformation`] is only displayed alongside border routers.
+    pub(crate) fn is_edge_data(&self) -> bool {
+        matches!(
+            self.class,
+            Some(EDGE_DATA_CLASS_NAME) | Some(EDGE_DATA_TEXT_CLASS_NAME)
+        )
     }
 
-    /// Calculates the apprroximate length in pixels of a [`TextInformation`] instance.
-    pub(crate) fn calculate_length(&self, pad: Option<FontSizeT>) -> Result<CoordinateT, SVGError> {
-        TextInformation::calculate_length_util(self.font_size.px, self.value.len(), pad)
+    /// The direction of the channel this text describes from its core's point of view, if any.
+    /// Source text is laid out from the source's point of view, so its `direction` is flipped back.
+    pub(crate) fn channel_direction(&self) -> Option<Directions> {
+        self.direction.map(|direction| match self.source {
+            true => utils::opposite_direction(&direction),
+            false => direction,
+        })
+    }
+
+    /// Changes the font size of a [`TextInformation`] instance.
+    pub(crate) fn set_font_size(&mut self, font_size: FontSizeT) {
+        self.font_size.px = font_size;
     }
 
     /// Creates a new [`TextInformation`] instance from the given parameters.
@@ -125,132 +179,164 @@ impl TextInformation {
             dominant_baseline,
             fill: match fill {
                 Some(f) => f.clone(),
-                None => "black".to_string(),
+                None => LIGHT_COLOURS.text.to_string(),
+            },
+            // Text with no requested colour follows the theme.
+            class: match (fill, class) {
+                (Some(_), class) => class,
+                (None, Some(EDGE_DATA_CLASS_NAME)) => Some(EDGE_DATA_TEXT_CLASS_NAME),
+                (None, Some(class)) => Some(class),
+                (None, None) => Some(TEXT_CLASS_NAME),
             },
-            class,
             value,
+            direction: None,
+            side: LinkSide::Primary,
+            source: false,
         }
     }
 
-    /// Shared logic used when generating "primary" [`TextInformation`] for a channel.
+    /// Shared logic used when generating [`TextInformation`] for a channel.
     /// The `relevant_delta` can either be x or y and is chosen depending on `direction`.
-    fn common_channel_primary(
+    /// Text is placed on the provided `side` of the link, `slot` attribute font sizes along it.
+    fn common_channel_text(
         link_x: &CoordinateT,
         link_y: &CoordinateT,
         direction: &Directions,
         relevant_delta: CoordinateT,
+        side: &LinkSide,
+        slot: CoordinateT,
         fill: Option<&String>,
         class: Option<&'static str>,
         data: String,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Self {
-        match direction {
-            Directions::North => {
-                let delta_y = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_add(HORIZONTAL_OFFSET_FROM_LINK),
-                    link_y.saturating_sub(delta_y),
-                    *processed_base_configuration.attribute_font_size(),
-                    "start",
-                    "middle",
-                    fill,
-                    class,
-                    data,
-                )
-            }
-            Directions::East => {
-                let delta_x = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_add(delta_x),
-                    link_y.saturating_sub(VERTICAL_OFFSET_FROM_LINK),
-                    *processed_base_configuration.attribute_font_size(),
-                    "middle",
-                    "text-after-edge",
-                    fill,
-                    class,
-                    data,
-                )
-            }
-            Directions::South => {
-                let delta_y = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_sub(HORIZONTAL_OFFSET_FROM_LINK),
-                    link_y.saturating_add(delta_y),
-                    *processed_base_configuration.attribute_font_size(),
-                    "end",
-                    "middle",
-                    fill,
-                    class,
-                    data,
-                )
+        let font_size = *processed_base_configuration.attribute_font_size();
+        let stack_delta = processed_base_configuration
+            .attribute_font_size_coordinate()
+            .saturating_mul(slot);
+
+        let mut ret = match direction {
+            Directions::North | Directions::South => {
+                let y = match direction {
+                    Directions::North => link_y.saturating_sub(relevant_delta),
+                    _ => link_y.saturating_add(relevant_delta),
+                }
+                .saturating_add(stack_delta);
+
+                // Primary text sits to the right of northbound links and to the left of southbound ones.
+                match (direction, side) {
+                    (Directions::North, LinkSide::Primary)
+                    | (Directions::South, LinkSide::Opposite) => TextInformation::new(
+                        link_x.saturating_add(HORIZONTAL_OFFSET_FROM_LINK),
+                        y,
+                        font_size,
+                        "start",
+                        "middle",
+                        fill,
+                        class,
+                        data,
+                    ),
+                    _ => TextInformation::new(
+                        link_x.saturating_sub(HORIZONTAL_OFFSET_FROM_LINK),
+                        y,
+                        font_size,
+                        "end",
+                        "middle",
+                        fill,
+                        class,
+                        data,
+                    ),
+                }
             }
-            Directions::West => {
-                let delta_x = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_sub(delta_x),
-                    link_y.saturating_add(VERTICAL_OFFSET_FROM_LINK),
-                    *processed_base_configuration.attribute_font_size(),
-                    "middle",
-                    "text-before-edge",
-                    fill,
-                    class,
-                    data,
-                )
+            Directions::East | Directions::West => {
+                let x = match direction {
+                    Directions::East => link_x.saturating_add(relevant_delta),
+                    _ => link_x.saturating_sub(relevant_delta),
+                };
+
+                // Primary text sits above eastbound links and below westbound ones.
+                match (direction, side) {
+                    (Directions::East, LinkSide::Primary)
+                    | (Directions::West, LinkSide::Opposite) => TextInformation::new(
+                        x,
+                        link_y
+                            .saturating_sub(VERTICAL_OFFSET_FROM_LINK)
+                            .saturating_sub(stack_delta),
+                        font_size,
+                        "middle",
+                        "text-after-edge",
+                        fill,
+                        class,
+                        data,
+                    ),
+                    _ => TextInformation::new(
+                        x,
+                        link_y
+                            .saturating_add(VERTICAL_OFFSET_FROM_LINK)
+                            .saturating_add(stack_delta),
+                        font_size,
+                        "middle",
+                        "text-before-edge",
+                        fill,
+                        class,
+                        data,
+                    ),
+                }
             }
-        }
+        };
+        ret.direction = Some(*direction);
+        ret.side = *side;
+
+        ret
     }
 
     /// Calculates the fill and load percentage of a channel.
-    fn calculate_load_fill_and_percentage<'a>(
+    pub(crate) fn calculate_load_fill_and_percentage(
         load: &u16,
         bandwidth: &u16,
-        routing_configuration: &'a RoutingConfiguration,
-    ) -> (Option<u16>, Option<&'a String>) {
+        routing_configuration: &RoutingConfiguration,
+    ) -> (Option<u16>, Option<String>) {
         if *bandwidth > 0 {
             // We can only calculaye load percentage if the bandwidth is above 0.
             let percentage =
                 ((FontSizeT::from(*load) / FontSizeT::from(*bandwidth)) * 100.0).round() as u16;
 
-            let fill_idx = utils::binary_search_left_insertion_point(
-                routing_configuration.load_colours().bounds(),
-                percentage.into(),
-            );
-
-            let fill = &routing_configuration.load_colours().colours()[fill_idx];
+            let fill =
+                utils::get_value_colour(routing_configuration.load_colours(), percentage.into());
             return (Some(percentage), Some(fill));
         } else {
             // If we can't calculate a load percentage, the channel is overloaded so we pick the last colour.
             return (
                 None,
-                Some(&routing_configuration.load_colours().colours()[3]),
+                routing_configuration
+                    .load_colours()
+                    .colours()
+                    .last()
+                    .cloned(),
             );
         }
     }
 
     /// Generates the text to display for a channel's load based on user provided configuration (`routing_configuration`).
+    /// Heatmap loads are never displayed as text, they style the link instead.
     fn generate_load_data(
         load: &u16,
         bandwidth: &u16,
         percentage: Option<u16>,
         routing_configuration: &RoutingConfiguration,
     ) -> String {
-        // Does the user want fraction or percentage?
-        match routing_configuration.load_configuration() {
-            LoadConfiguration::Percentage => match percentage {
-                Some(value) => format!("{}: {}%", routing_configuration.display(), value),
-                // We can't give them a percentage for a channel with no bandwidth -> default to fraction.
-                None => format!(
-                    "{}: {}/{}",
-                    routing_configuration.display(),
-                    load,
-                    bandwidth
-                ),
-            },
-            LoadConfiguration::Fraction => format!(
+        // Does the user want percentage? We can't give them one for a channel with no bandwidth,
+        // in which case we default to fraction.
+        let percentage = percentage.filter(|_| {
+            matches!(
+                routing_configuration.load_configuration(),
+                LoadConfiguration::Percentage
+            )
+        });
+
+        match percentage {
+            Some(value) => format!("{}: {}%", routing_configuration.display(), value),
+            None => format!(
                 "{}: {}/{}",
                 routing_configuration.display(),
                 load,
@@ -269,12 +355,7 @@ impl TextInformation {
         routing_configuration: &RoutingConfiguration,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Self {
-        let relevant_delta: i32 = match direction {
-            Directions::North => SOUTH_SINKS_SOURCES_CONNECTION_DELTA,
-            Directions::East => WEST_SINKS_SOURCES_CONNECTION_DELTA,
-            Directions::South => NORTH_SINKS_SOURCES_CONNECTION_DELTA,
-            Directions::West => EAST_SINKS_SOURCES_CONNECTION_DELTA,
-        };
+        let relevant_delta = TextInformation::source_delta(direction, processed_base_configuration);
 
         let (percentage, fill) = TextInformation::calculate_load_fill_and_percentage(
             load,
@@ -284,38 +365,66 @@ impl TextInformation {
         let data =
             TextInformation::generate_load_data(load, bandwidth, percentage, routing_configuration);
 
-        TextInformation::common_channel_primary(
+        let mut ret = TextInformation::common_channel_text(
             link_x,
             link_y,
             direction,
             relevant_delta,
-            fill,
+            &LinkSide::Primary,
+            0,
+            fill.as_ref(),
             Some(EDGE_DATA_CLASS_NAME),
             data,
             processed_base_configuration,
-        )
+        );
+        ret.source = true;
+
+        ret
+    }
+
+    /// Calculates the coordinate delta for a source link data.
+    /// `direction` is seen from the source, i.e. flipped from the core's point of view.
+    fn source_delta(
+        direction: &Directions,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> CoordinateT {
+        let sinks_sources_geometry = processed_base_configuration.sinks_sources_geometry();
+
+        match direction {
+            Directions::North => *sinks_sources_geometry.south_connection_delta(),
+            Directions::East => *sinks_sources_geometry.west_connection_delta(),
+            Directions::South => *sinks_sources_geometry.north_connection_delta(),
+            Directions::West => *sinks_sources_geometry.east_connection_delta(),
+        }
     }
 
     /// Calculates the coordinate delta and required class for a link data.
     fn link_delta_and_class(
         edge: bool,
         direction: &Directions,
+        processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> (CoordinateT, Option<&'static str>) {
         if edge {
+            let sinks_sources_geometry = processed_base_configuration.sinks_sources_geometry();
+
             return (
                 match direction {
-                    Directions::North => NORTH_SINKS_SOURCES_CONNECTION_DELTA,
-                    Directions::West => WEST_SINKS_SOURCES_CONNECTION_DELTA,
-                    Directions::East => EAST_SINKS_SOURCES_CONNECTION_DELTA,
-                    Directions::South => SOUTH_SINKS_SOURCES_CONNECTION_DELTA,
+                    Directions::North => *sinks_sources_geometry.north_connection_delta(),
+                    Directions::West => *sinks_sources_geometry.west_connection_delta(),
+                    Directions::East => *sinks_sources_geometry.east_connection_delta(),
+                    Directions::South => *sinks_sources_geometry.south_connection_delta(),
                 },
                 Some(EDGE_DATA_CLASS_NAME),
             );
         }
 
+        let geometry = processed_base_configuration.geometry();
+
         return match direction {
-            Directions::North | Directions::South => (HALF_CONNECTION_LENGTH, None),
-            Directions::East | Directions::West => (THREE_QUARTERS_CONNECTION_LENGTH, None),
+            Directions::North | Directions::South => (*geometry.half_connection_length(), None),
+            Directions::East | Directions::West => {
+                (*geometry.three_quarters_connection_length(), None)
+            }
         };
     }
 
@@ -330,7 +439,8 @@ impl TextInformation {
         routing_configuration: &RoutingConfiguration,
         prrocessed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Self {
-        let (relevant_delta, class) = TextInformation::link_delta_and_class(edge, direction);
+        let (relevant_delta, class) =
+            TextInformation::link_delta_and_class(edge, direction, prrocessed_base_configuration);
 
         let (percentage, fill) = TextInformation::calculate_load_fill_and_percentage(
             load,
@@ -340,160 +450,147 @@ impl TextInformation {
         let data =
             TextInformation::generate_load_data(load, bandwidth, percentage, routing_configuration);
 
-        TextInformation::common_channel_primary(
+        TextInformation::common_channel_text(
             link_x,
             link_y,
             direction,
             relevant_delta,
-            fill,
+            &LinkSide::Primary,
+            0,
+            fill.as_ref(),
             class,
             data,
             prrocessed_base_configuration,
         )
     }
 
-    /// Generates [`TextInformation`] for a channel field as primary channel text attribute.
-    pub(crate) fn link_primary(
+    /// Generates [`TextInformation`] for a channel field, in the given `slot` on the `side` of its link.
+    /// Slot 0 is where the load of routed channels is displayed, further slots stack next to it.
+    pub(crate) fn link_attribute(
         direction: &Directions,
         link_x: &CoordinateT,
         link_y: &CoordinateT,
+        core_id: &ElementIDT,
+        key: &str,
         data: &String,
         edge: bool,
+        side: &LinkSide,
+        slot: CoordinateT,
         field_configuration: &FieldConfiguration,
+        diagnostics: &mut Vec<Diagnostic>,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Self {
-        let (relevant_delta, class) = TextInformation::link_delta_and_class(edge, direction);
+        let (relevant_delta, class) =
+            TextInformation::link_delta_and_class(edge, direction, processed_base_configuration);
 
-        let (fill, data) = match field_configuration {
-            FieldConfiguration::ColouredText {
-                display: value,
-                colour_settings,
-            } => (
-                utils::get_attribute_colour(
-                    colour_settings.bounds(),
-                    colour_settings.colours(),
-                    data,
-                ),
-                format!("{}: {}", value, data),
-            ),
-            FieldConfiguration::Text { display, colour } => {
-                (colour.as_ref(), format!("{}: {}", display, data))
-            }
-            _ => (None, "".into()), // Unsupported
+        // Southbound slots grow towards the link's end. On edge links, that is the border router.
+        let slot = match (edge, direction) {
+            (true, Directions::South) => slot.saturating_neg(),
+            _ => slot,
         };
 
-        TextInformation::common_channel_primary(
+        let (fill, data) = TextInformation::field_fill_and_data(
+            direction,
+            core_id,
+            key,
+            data,
+            field_configuration,
+            diagnostics,
+        );
+
+        TextInformation::common_channel_text(
             link_x,
             link_y,
             direction,
             relevant_delta,
-            fill,
+            side,
+            slot,
+            fill.as_ref(),
             class,
             data,
             processed_base_configuration,
         )
     }
 
-    /// Generates [`TextInformation`] for a channel field as secondary channel text attribute.
-    pub(crate) fn link_secondary(
+    /// Generates [`TextInformation`] for a channel field on the source link of an edge `direction`,
+    /// in the given `slot` on the `side` of the link. Slot 0 is where the source load is displayed.
+    pub(crate) fn source_attribute(
         direction: &Directions,
         link_x: &CoordinateT,
         link_y: &CoordinateT,
+        core_id: &ElementIDT,
+        key: &str,
         data: &String,
+        side: &LinkSide,
+        slot: CoordinateT,
         field_configuration: &FieldConfiguration,
+        diagnostics: &mut Vec<Diagnostic>,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Self {
-        // This function is called only for non edge links. Core output edge links cannot have secondary information, even if present,
-        // due to simmetry. The information would be missing on their input counterpart as that channel is not present in the XML file.
-        let (relevant_delta, class) = TextInformation::link_delta_and_class(false, direction);
+        // Source text is laid out from the source point of view, not the core's.
+        let flipped_direction = utils::opposite_direction(direction);
+        let relevant_delta =
+            TextInformation::source_delta(&flipped_direction, processed_base_configuration);
+
+        // Northbound slots grow towards the link's start. On source links, that is the border router.
+        let slot = match flipped_direction {
+            Directions::North => slot.saturating_neg(),
+            _ => slot,
+        };
+
+        let (fill, data) = TextInformation::field_fill_and_data(
+            direction,
+            core_id,
+            key,
+            data,
+            field_configuration,
+            diagnostics,
+        );
 
-        let (fill, display) = match field_configuration {
+        let mut ret = TextInformation::common_channel_text(
+            link_x,
+            link_y,
+            &flipped_direction,
+            relevant_delta,
+            side,
+            slot,
+            fill.as_ref(),
+            Some(EDGE_DATA_CLASS_NAME),
+            data,
+            processed_base_configuration,
+        );
+        ret.source = true;
+
+        ret
+    }
+
+    /// Calculates the fill and text of a channel field, according to its [`FieldConfiguration`].
+    fn field_fill_and_data(
+        direction: &Directions,
+        core_id: &ElementIDT,
+        key: &str,
+        data: &String,
+        field_configuration: &FieldConfiguration,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> (Option<String>, String) {
+        match field_configuration {
             FieldConfiguration::ColouredText {
                 display: value,
                 colour_settings,
             } => (
                 utils::get_attribute_colour(
-                    colour_settings.bounds(),
-                    colour_settings.colours(),
+                    colour_settings,
+                    &utils::channel_element(core_id, direction),
+                    key,
                     data,
+                    diagnostics,
                 ),
                 format!("{}: {}", value, data),
             ),
             FieldConfiguration::Text { display, colour } => {
-                (colour.as_ref(), format!("{}: {}", display, data))
-            }
-            _ => (None, "".into()), // Any other variant shouldn't be used.
-        };
-
-        match direction {
-            Directions::North => {
-                let delta_y = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_add(HORIZONTAL_OFFSET_FROM_LINK),
-                    link_y.saturating_sub(delta_y).saturating_add(
-                        *processed_base_configuration.attribute_font_size_coordinate(),
-                    ),
-                    *processed_base_configuration.attribute_font_size(),
-                    "start",
-                    "middle",
-                    fill,
-                    class,
-                    display,
-                )
-            }
-            Directions::East => {
-                let delta_x = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_add(delta_x),
-                    link_y
-                        .saturating_sub(VERTICAL_OFFSET_FROM_LINK)
-                        .saturating_sub(
-                            *processed_base_configuration.attribute_font_size_coordinate(),
-                        ),
-                    *processed_base_configuration.attribute_font_size(),
-                    "middle",
-                    "text-after-edge",
-                    fill,
-                    class,
-                    display,
-                )
-            }
-            Directions::South => {
-                let delta_y = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_sub(HORIZONTAL_OFFSET_FROM_LINK),
-                    link_y.saturating_add(delta_y).saturating_add(
-                        *processed_base_configuration.attribute_font_size_coordinate(),
-                    ),
-                    *processed_base_configuration.attribute_font_size(),
-                    "end",
-                    "middle",
-                    fill,
-                    class,
-                    display,
-                )
-            }
-            Directions::West => {
-                let delta_x = relevant_delta;
-
-                TextInformation::new(
-                    link_x.saturating_sub(delta_x),
-                    link_y
-                        .saturating_add(VERTICAL_OFFSET_FROM_LINK)
-                        .saturating_add(
-                            *processed_base_configuration.attribute_font_size_coordinate(),
-                        ),
-                    *processed_base_configuration.attribute_font_size(),
-                    "middle",
-                    "text-before-edge",
-                    fill,
-                    class,
-                    display,
-                )
+                (colour.clone(), format!("{}: {}", display, data))
             }
+            _ => (None, "".into()), // Unsupported
         }
     }
 }
@@ -5,18 +5,22 @@ use std::{
 
 use manycore_parser::{Directions, ElementIDT, WithID, WithXMLAttributes, COORDINATES_KEY, ID_KEY};
 
-use super::{ProcessingInformation, TextInformation, OFFSET_FROM_BORDER};
+use super::{
+    colour_interpolation::interpolate_colours, LabelStack, ProcessingInformation,
+    OFFSET_FROM_BORDER,
+};
 use crate::{
-    generation_error, ConnectionType, ConnectionsParentGroup, CoordinateT, DirectionType,
-    FieldConfiguration, ProcessedBaseConfiguration, SVGError, SVGErrorKind,
+    generation_error, style::ElementRule, ClipPath, ColourInterpolation, ColourSettings,
+    ConnectionType, ConnectionsParentGroup, CoordinateT, Diagnostic, DirectionType,
+    FieldConfiguration, LinkSide, ProcessedBaseConfiguration, SVGError, SVGErrorKind,
 };
 
-/// Binary search to fit input value in one of the 4 boundaries.
-pub(crate) fn binary_search_left_insertion_point(bounds: &[u64; 4], val: u64) -> usize {
-    // Bounds has always length 4
-    let mut l: i8 = 0;
-    let max_i: i8 = 3;
-    let mut r: i8 = max_i;
+/// Binary search to fit input value in one of the boundaries.
+pub(crate) fn binary_search_left_insertion_point(bounds: &[f64], val: f64) -> usize {
+    // Bounds length is validated when deserialising colour settings, it's always small and never empty.
+    let mut l: isize = 0;
+    let max_i: isize = bounds.len() as isize - 1;
+    let mut r: isize = max_i;
 
     while l <= r {
         let m = l + (r - l) / 2;
@@ -29,7 +33,7 @@ pub(crate) fn binary_search_left_insertion_point(bounds: &[u64; 4], val: u64) ->
         }
     }
 
-    // We could go out of bounds, but that's meaningless for us. Constrain between 0 and 3
+    // We could go out of bounds, but that's meaningless for us. Constrain between 0 and the last bound
     let corrected_l = max(min(l, max_i), 0) as usize;
 
     // We found the left most insertion point
@@ -42,37 +46,71 @@ pub(crate) fn binary_search_left_insertion_point(bounds: &[u64; 4], val: u64) ->
     }
 }
 
+/// Calculates the colour of a numerical value according to the provided [`ColourSettings`].
+pub(crate) fn get_value_colour(colour_settings: &ColourSettings, value: f64) -> String {
+    let bounds = colour_settings.bounds();
+    let colours = colour_settings.colours();
+    let idx = binary_search_left_insertion_point(bounds, value);
+
+    if let ColourInterpolation::Discrete = colour_settings.interpolation() {
+        return colours[idx].clone();
+    }
+
+    // Values below the first or above the last stop take the closest stop's colour.
+    let next = idx.saturating_add(1);
+    if value <= bounds[idx] || next >= bounds.len() {
+        return colours[idx].clone();
+    }
+
+    let (from, to) = (bounds[idx], bounds[next]);
+    let t = (value - from) / (to - from);
+
+    // Fall back to the discrete colour if a colour can't be interpolated (e.g. not hexadecimal).
+    interpolate_colours(
+        &colours[idx],
+        &colours[next],
+        t,
+        colour_settings.interpolation(),
+    )
+    .unwrap_or_else(|| colours[idx].clone())
+}
+
 /// Generates [`InformationLayer`] content for a [`WithID`] element.
+/// Text lines are stacked inside the element's `clip_path`, those that don't fit are reported in `diagnostics`.
 pub(crate) fn generate_with_id<T: WithID<ElementIDT> + WithXMLAttributes>(
-    mut base_x: CoordinateT,
-    mut base_y: CoordinateT,
+    base_x: CoordinateT,
+    base_y: CoordinateT,
     configuration: &BTreeMap<String, FieldConfiguration>,
     fill_override: &BTreeMap<ElementIDT, String>,
     target: &T,
     group: &mut ProcessingInformation,
     text_anchor: &'static str,
-    css: &mut String,
+    clip_path: &ClipPath,
+    element_rules: &mut Vec<ElementRule>,
+    diagnostics: &mut Vec<Diagnostic>,
     processed_base_configuration: &ProcessedBaseConfiguration,
 ) -> Result<(), SVGError> {
     // Start by adding some padding between text and element border
-    base_x = base_x.saturating_add(OFFSET_FROM_BORDER);
+    let mut lines = LabelStack::new(
+        base_x.saturating_add(OFFSET_FROM_BORDER),
+        base_y,
+        text_anchor,
+        clip_path,
+        format!("{}{}", target.variant(), target.id()),
+        processed_base_configuration,
+    );
 
     // ID value is outside of attributes map
     if let Some(configuration) = configuration.get(ID_KEY) {
         match configuration {
             FieldConfiguration::Text { display, colour } => {
-                group.information.push(TextInformation::new(
-                    base_x,
-                    base_y,
-                    *processed_base_configuration.attribute_font_size(),
-                    text_anchor,
-                    "text-before-edge",
+                lines.push(
+                    group,
+                    ID_KEY,
                     colour.as_ref(),
-                    None,
                     format!("{}: {}", display, target.id()),
-                ));
-                base_y = base_y
-                    .saturating_add(*processed_base_configuration.attribute_font_size_coordinate());
+                    diagnostics,
+                );
                 Ok(())
             }
             fc => Err(generation_error(format!(
@@ -98,44 +136,32 @@ pub(crate) fn generate_with_id<T: WithID<ElementIDT> + WithXMLAttributes>(
                         match field_configuration {
                             FieldConfiguration::Text { display, colour } => {
                                 // Simple Text
-                                group.information.push(TextInformation::new(
-                                    base_x,
-                                    base_y,
-                                    *processed_base_configuration.attribute_font_size(),
-                                    text_anchor,
-                                    "text-before-edge",
+                                lines.push(
+                                    group,
+                                    valid_key,
                                     colour.as_ref(),
-                                    None,
                                     format!("{}: {}", display, value),
-                                ));
-
-                                // Increase y for next element, if any
-                                base_y = base_y.saturating_add(
-                                    *processed_base_configuration.attribute_font_size_coordinate(),
+                                    diagnostics,
                                 );
                             }
-                            FieldConfiguration::Fill { colour_settings } => {
+                            FieldConfiguration::Fill {
+                                colour_settings, ..
+                            } => {
                                 // Do not compute if user requested override
                                 if let None = fill_override.get(target.id()) {
-                                    // Fill colour
-                                    let bounds = colour_settings.bounds();
+                                    let element = format!("{}{}", target.variant(), target.id());
 
                                     // If we can't parse it as a number, we can't calculate what the fill colour should be.
-                                    // TODO: Conversion error instead?
-                                    if let Ok(value_num) = value.parse::<u64>() {
-                                        let fill_idx =
-                                            binary_search_left_insertion_point(bounds, value_num);
-
+                                    if let Some(fill) = get_attribute_colour(
+                                        colour_settings,
+                                        &element,
+                                        valid_key,
+                                        value,
+                                        diagnostics,
+                                    ) {
                                         // Add fill colour in the [`SVG`] CSS
-                                        css.push_str(
-                                            format!(
-                                                "\n#{}{} {{fill: {};}}",
-                                                target.variant(),
-                                                target.id(),
-                                                colour_settings.colours()[fill_idx]
-                                            )
-                                            .as_str(),
-                                        );
+                                        element_rules
+                                            .push(ElementRule::new(element, vec![("fill", fill)]));
                                     }
                                 }
                             }
@@ -145,25 +171,19 @@ pub(crate) fn generate_with_id<T: WithID<ElementIDT> + WithXMLAttributes>(
                             } => {
                                 // Coloured text
                                 let fill = get_attribute_colour(
-                                    colour_settings.bounds(),
-                                    colour_settings.colours(),
+                                    colour_settings,
+                                    &format!("{}{}", target.variant(), target.id()),
+                                    valid_key,
                                     value,
+                                    diagnostics,
                                 );
 
-                                group.information.push(TextInformation::new(
-                                    base_x,
-                                    base_y,
-                                    *processed_base_configuration.attribute_font_size(),
-                                    text_anchor,
-                                    "text-before-edge",
-                                    fill,
-                                    None,
+                                lines.push(
+                                    group,
+                                    valid_key,
+                                    fill.as_ref(),
                                     format!("{}: {}", title, value),
-                                ));
-
-                                // Increase y for next element, if any
-                                base_y = base_y.saturating_add(
-                                    *processed_base_configuration.attribute_font_size_coordinate(),
+                                    diagnostics,
                                 );
                             }
                             _ => {
@@ -176,32 +196,75 @@ pub(crate) fn generate_with_id<T: WithID<ElementIDT> + WithXMLAttributes>(
         }
     }
 
+    lines.finish(group);
+
     // Did the user request to override fill colour?
     if let Some(fill) = fill_override.get(target.id()) {
         // Add fill colour in the [`SVG`] CSS
-        css.push_str(
-            format!("\n#{}{} {{fill: {};}}", target.variant(), target.id(), fill).as_str(),
-        );
+        element_rules.push(ElementRule::new(
+            format!("{}{}", target.variant(), target.id()),
+            vec![("fill", fill.clone())],
+        ));
     }
 
     Ok(())
 }
 
-/// Calculates the corresponding colour for an attribute value given some bounds.
-pub(crate) fn get_attribute_colour<'a>(
-    bounds: &'a [u64; 4],
-    colours: &'a [String; 4],
-    attribute_value: &'a String,
-) -> Option<&'a String> {
-    let mut fill: Option<&String> = None;
-
-    // TODO: Conversion errorr instead?
-    if let Ok(value_num) = attribute_value.parse::<u64>() {
-        let fill_idx = binary_search_left_insertion_point(bounds, value_num);
-        fill = Some(&colours[fill_idx]);
+/// Parses an attribute value for colour mapping.
+/// Integers, decimals, signed values and scientific notation are all accepted, as long as the value is finite.
+fn parse_colour_value(attribute_value: &str) -> Option<f64> {
+    attribute_value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+}
+
+/// Calculates the corresponding colour for an attribute value given some [`ColourSettings`].
+/// Values that can't be parsed as a number are reported in `diagnostics`.
+pub(crate) fn get_attribute_colour(
+    colour_settings: &ColourSettings,
+    element: &str,
+    key: &str,
+    attribute_value: &String,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    match parse_colour_value(attribute_value) {
+        Some(value) => Some(get_value_colour(colour_settings, value)),
+        None => {
+            diagnostics.push(Diagnostic::unparseable_number(
+                element.to_string(),
+                key,
+                attribute_value,
+            ));
+
+            None
+        }
+    }
+}
+
+/// Generates the [`Diagnostic`] element description of a channel.
+pub(crate) fn channel_element(core_id: &ElementIDT, direction: &Directions) -> String {
+    format!("{} channel of c{}", direction, core_id)
+}
+
+/// Returns the direction opposite to the provided one.
+pub(crate) fn opposite_direction(direction: &Directions) -> Directions {
+    match direction {
+        Directions::North => Directions::South,
+        Directions::South => Directions::North,
+        Directions::East => Directions::West,
+        Directions::West => Directions::East,
     }
+}
 
-    fill
+/// Returns the direction channel text on the `side` of a `direction` link is laid out for.
+/// Text on the opposite side of a link sits where primary text of the opposite link would.
+pub(crate) fn layout_direction(direction: &Directions, side: &LinkSide) -> Directions {
+    match side {
+        LinkSide::Primary => *direction,
+        LinkSide::Opposite => opposite_direction(direction),
+    }
 }
 
 /// Determines the type of an SVG connection: Input/Output.
@@ -255,3 +318,69 @@ pub(crate) fn missing_source_load(core_id: &ElementIDT, direction: &Directions)
         direction, core_id
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_attribute_colour, get_value_colour};
+    use crate::ColourSettings;
+
+    fn colour_settings(colours: &str, interpolation: &str) -> ColourSettings {
+        serde_json::from_str(&format!(
+            r#"{{"bounds": [0, 100], "colours": {colours}, "interpolation": "{interpolation}"}}"#
+        ))
+        .expect("Could not parse colour settings")
+    }
+
+    #[test]
+    fn can_interpolate_value_colour() {
+        let linear = colour_settings(r##"["#000000", "#ffffff"]"##, "LinearRgb");
+        let oklab = colour_settings(r##"["#000000", "#ffffff"]"##, "Oklab");
+
+        assert_eq!(get_value_colour(&linear, 50.0), "#bcbcbc");
+        assert_eq!(get_value_colour(&oklab, 50.0), "#636363");
+    }
+
+    #[test]
+    fn clamps_value_colour_to_bounds() {
+        let linear = colour_settings(r##"["#000000", "#ffffff"]"##, "LinearRgb");
+
+        assert_eq!(get_value_colour(&linear, -10.0), "#000000");
+        assert_eq!(get_value_colour(&linear, 0.0), "#000000");
+        assert_eq!(get_value_colour(&linear, 100.0), "#ffffff");
+        assert_eq!(get_value_colour(&linear, 150.0), "#ffffff");
+    }
+
+    #[test]
+    fn falls_back_to_discrete_for_non_hex_colours() {
+        let named = colour_settings(r#"["black", "white"]"#, "Oklab");
+
+        assert_eq!(get_value_colour(&named, 50.0), "black");
+        assert_eq!(get_value_colour(&named, 150.0), "white");
+    }
+
+    #[test]
+    fn can_colour_decimal_signed_and_scientific_values() {
+        let discrete = colour_settings(r##"["#000000", "#ffffff"]"##, "Discrete");
+        let mut diagnostics = Vec::new();
+        let mut colour = |value: &str| {
+            get_attribute_colour(
+                &discrete,
+                "c0",
+                "@temperature",
+                &value.to_string(),
+                &mut diagnostics,
+            )
+        };
+
+        assert_eq!(colour("12.5"), Some("#000000".to_string()));
+        assert_eq!(colour("-3.5"), Some("#000000".to_string()));
+        assert_eq!(colour("1e2"), Some("#ffffff".to_string()));
+        assert_eq!(colour(" 150 "), Some("#ffffff".to_string()));
+        assert_eq!(colour("High"), None);
+        assert_eq!(colour("inf"), None);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].value(), "High");
+        assert_eq!(diagnostics[1].value(), "inf");
+    }
+}
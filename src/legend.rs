@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    ColourInterpolation, ColourSettings, Configuration, CoordinateT, FieldConfiguration, Offsets,
+    ProcessedBaseConfiguration, RoutingConfiguration, SVGError, TextInformation, ViewBox,
+    CHAR_H_PADDING, CHAR_V_PADDING, CORE_ROUTER_STROKE_WIDTH_STR, LIGHT_COLOURS, STROKE_CLASS_NAME,
+};
+
+#[cfg(doc)]
+use crate::SVG;
+
+static LEGEND_MARGIN: CoordinateT = 50;
+static LEGEND_SCALE_GAP: CoordinateT = 20;
+static SWATCH_GAP: CoordinateT = 10;
+
+/// Object representation of a legend colour swatch SVG `<rect>`.
+#[derive(Serialize)]
+struct Swatch {
+    #[serde(rename = "@x")]
+    x: CoordinateT,
+    #[serde(rename = "@y")]
+    y: CoordinateT,
+    #[serde(rename = "@width")]
+    width: CoordinateT,
+    #[serde(rename = "@height")]
+    height: CoordinateT,
+    #[serde(rename = "@class")]
+    class: &'static str,
+    #[serde(rename = "@fill")]
+    fill: String,
+    #[serde(rename = "@stroke")]
+    stroke: &'static str,
+    #[serde(rename = "@stroke-width")]
+    stroke_width: &'static str,
+}
+
+/// Object representation of the legend SVG `<g>` for a single colour scale.
+#[derive(Serialize)]
+struct LegendScale {
+    #[serde(rename = "text")]
+    title: TextInformation,
+    #[serde(rename = "rect")]
+    swatches: Vec<Swatch>,
+    #[serde(rename = "text")]
+    labels: Vec<TextInformation>,
+}
+
+/// Object representation of the legend SVG `<g>`.
+/// Lists every active colour scale, with its colour swatches and bound ranges.
+#[derive(Serialize)]
+#[serde(rename = "g")]
+pub(crate) struct Legend {
+    #[serde(rename = "@id")]
+    id: &'static str,
+    #[serde(rename = "g")]
+    scales: Vec<LegendScale>,
+    #[serde(skip)]
+    offsets: Offsets,
+}
+
+/// Generates the label of each [`ColourSettings`] colour.
+/// Discrete scales show the range of values each colour covers, continuous ones show the value of each stop.
+fn swatch_labels(colour_settings: &ColourSettings) -> Vec<String> {
+    let bounds = colour_settings.bounds();
+    let last = bounds.len().saturating_sub(1);
+
+    bounds
+        .iter()
+        .enumerate()
+        .map(|(i, bound)| match colour_settings.interpolation() {
+            ColourInterpolation::Discrete => match i {
+                0 => format!("< {}", bounds[1]),
+                i if i == last => format!("≥ {}", bound),
+                i => format!("{} to {}", bound, bounds[i + 1]),
+            },
+            _ => format!("{}", bound),
+        })
+        .collect()
+}
+
+impl Legend {
+    /// Generates a new [`Legend`] listing the colour scales in the provided [`Configuration`] and
+    /// [`RoutingConfiguration`]. The legend is placed to the right of the provided [`ViewBox`].
+    /// Returns [`None`] if there is no colour scale to list.
+    pub(crate) fn new(
+        configuration: &Configuration,
+        routing_configuration: Option<&RoutingConfiguration>,
+        view_box: &ViewBox,
+        processed_base_configuration: &ProcessedBaseConfiguration,
+    ) -> Result<Option<Self>, SVGError> {
+        let mut scales_settings: Vec<(String, &ColourSettings)> = Vec::new();
+
+        let mut push_element_scales =
+            |element: &str, element_configuration: &BTreeMap<String, FieldConfiguration>| {
+                for (key, field_configuration) in element_configuration {
+                    match field_configuration {
+                        FieldConfiguration::Fill {
+                            display,
+                            colour_settings,
+                        } => scales_settings.push((
+                            format!(
+                                "{}: {}",
+                                element,
+                                display
+                                    .as_deref()
+                                    .unwrap_or_else(|| key.trim_start_matches('@'))
+                            ),
+                            colour_settings,
+                        )),
+                        FieldConfiguration::ColouredText {
+                            display,
+                            colour_settings,
+                        } => scales_settings
+                            .push((format!("{}: {}", element, display), colour_settings)),
+                        _ => {}
+                    }
+                }
+            };
+
+        push_element_scales("Core", configuration.core_config());
+        push_element_scales("Router", configuration.router_config());
+        push_element_scales("Channel", configuration.channel_config());
+
+        if let Some(routing_configuration) = routing_configuration {
+            scales_settings.push((
+                format!(
+                    "Channel: {} (% of bandwidth)",
+                    routing_configuration.display()
+                ),
+                routing_configuration.load_colours(),
+            ));
+        }
+
+        if scales_settings.is_empty() {
+            return Ok(None);
+        }
+
+        let font_size = *processed_base_configuration.attribute_font_size();
+        let row_height = processed_base_configuration
+            .attribute_font_size_coordinate()
+            .saturating_add(CHAR_V_PADDING);
+        let swatch_side = *processed_base_configuration.attribute_font_size_coordinate();
+
+        let x = view_box
+            .x
+            .saturating_add(view_box.width)
+            .saturating_add(LEGEND_MARGIN);
+        let top = view_box.y;
+        let mut y = top;
+        let mut right = x;
+
+        let mut scales = Vec::with_capacity(scales_settings.len());
+
+        for (title, colour_settings) in scales_settings {
+            let title = TextInformation::new(
+                x,
+                y,
+                font_size,
+                "start",
+                "text-before-edge",
+                None,
+                None,
+                title,
+            );
+            right = right.max(x.saturating_add(title.calculate_length(Some(CHAR_H_PADDING))));
+            y = y.saturating_add(row_height);
+
+            let label_x = x.saturating_add(swatch_side).saturating_add(SWATCH_GAP);
+            let mut swatches = Vec::with_capacity(colour_settings.colours().len());
+            let mut labels = Vec::with_capacity(colour_settings.colours().len());
+
+            for (colour, label) in colour_settings
+                .colours()
+                .iter()
+                .zip(swatch_labels(colour_settings))
+            {
+                swatches.push(Swatch {
+                    x,
+                    y,
+                    width: swatch_side,
+                    height: swatch_side,
+                    class: STROKE_CLASS_NAME,
+                    fill: colour.clone(),
+                    stroke: LIGHT_COLOURS.stroke,
+                    stroke_width: CORE_ROUTER_STROKE_WIDTH_STR,
+                });
+
+                let label = TextInformation::new(
+                    label_x,
+                    y,
+                    font_size,
+                    "start",
+                    "text-before-edge",
+                    None,
+                    None,
+                    label,
+                );
+                right =
+                    right.max(label_x.saturating_add(label.calculate_length(Some(CHAR_H_PADDING))));
+                labels.push(label);
+
+                y = y.saturating_add(row_height);
+            }
+
+            scales.push(LegendScale {
+                title,
+                swatches,
+                labels,
+            });
+
+            y = y.saturating_add(LEGEND_SCALE_GAP);
+        }
+
+        Ok(Some(Self {
+            id: "legend",
+            scales,
+            offsets: Offsets::new(x, top, right, y.saturating_sub(LEGEND_SCALE_GAP)),
+        }))
+    }
+
+    /// Returns the viewBox [`Offsets`] required to fully display the [`Legend`] in the [`SVG`].
+    pub(crate) fn offsets(&self) -> Offsets {
+        self.offsets
+    }
+}
@@ -2,45 +2,74 @@
 //!
 //! Provides utilities to generate and customise an SVG file rerpresenting a ManyCore system.
 
+mod accessibility;
+mod animation;
 mod clip_path;
+mod communications_overlay;
 mod connections_group;
+mod data_attributes;
 mod defs;
+mod diagnostic;
 mod error;
+mod flow_highlight;
 mod information_group;
 mod information_layer;
+mod legend;
 mod marker;
 mod offsets;
 mod partial_update;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "png")]
+mod png;
 mod processing_group;
 mod render_settings;
+mod serialise_options;
 mod sinks_sources_layer;
 mod style;
 mod svg_conversions;
 mod tasks_group;
+mod text_metrics;
+mod theme;
+mod tooltip;
 mod view_box;
 
+use accessibility::{AccessibleText, GroupLabel};
+use animation::Animation;
 pub use clip_path::*;
+use communications_overlay::CommunicationsOverlay;
 use connections_group::*;
+use data_attributes::{direction_str, DataAttributes, LinkKind};
 use defs::*;
+pub use diagnostic::*;
 pub use error::*;
+use flow_highlight::FlowHighlight;
+pub use flow_highlight::FlowSelection;
 use getset::{Getters, MutGetters, Setters};
 use information_group::*;
 use information_layer::*;
+use legend::Legend;
 use marker::*;
 use offsets::*;
 use partial_update::PartialUpdate;
+#[cfg(feature = "png")]
+pub use png::*;
 use processing_group::*;
 pub use render_settings::*;
+pub use serialise_options::*;
 use sinks_sources_layer::SinksSourcesGroup;
 use tasks_group::{missing_task, TasksGroup};
+pub use theme::*;
+use tooltip::Tooltip;
 pub use view_box::*;
 
 use manycore_parser::{
-    ManycoreSystem, SystemDimensionsT, WithID, BORDER_ROUTERS_KEY, ROUTING_KEY, TASK_COST_KEY,
+    ManycoreSystem, RoutingAlgorithms, SystemDimensionsT, WithID, BORDER_ROUTERS_KEY, ROUTING_KEY,
+    TASK_COST_KEY,
 };
 
 use serde::Serialize;
-use style::Style;
+use style::{ExternalFont, FontSubset, Style};
 
 /// Type alias for SVG elements coordinates.
 pub type CoordinateT = i32;
@@ -66,6 +95,10 @@ struct Root {
     sinks_sources_group: SinksSourcesGroup,
     #[serde(rename = "g")]
     tasks_group: TasksGroup,
+    #[serde(rename = "g", skip_serializing_if = "Option::is_none")]
+    animation: Option<Animation>,
+    #[serde(rename = "g", skip_serializing_if = "Option::is_none")]
+    flow_highlight: Option<FlowHighlight>,
 }
 
 /// An Object representation of the [`ViewBox`] top left coordinate.
@@ -78,7 +111,7 @@ struct TopLeft {
 }
 
 /// Object representation of the generated SVGs.
-#[derive(Serialize, Getters, MutGetters)]
+#[derive(Serialize, Getters, MutGetters, Setters)]
 #[serde(rename = "svg")]
 pub struct SVG {
     #[serde(rename = "@xmlns:svg")]
@@ -92,6 +125,14 @@ pub struct SVG {
     #[serde(rename = "@viewBox")]
     #[getset(get = "pub", get_mut = "pub")]
     view_box: ViewBox,
+    #[serde(rename = "@role", skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    #[serde(rename = "@aria-labelledby", skip_serializing_if = "Option::is_none")]
+    aria_labelledby: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<AccessibleText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    desc: Option<AccessibleText>,
     defs: Defs,
     style: Style,
     #[serde(rename = "g")]
@@ -117,12 +158,22 @@ pub struct SVG {
     base_configuration: BaseConfiguration,
     #[serde(skip)]
     processed_base_configuration: ProcessedBaseConfiguration,
+    /// [`SerialiseOptions`] used for [`UpdateResult`]s and flow highlights. Compact by default.
+    #[serde(skip)]
+    #[getset(get = "pub")]
+    update_serialise_options: SerialiseOptions,
+    /// Font used by [`FontEmbedding::External`].
+    #[serde(skip)]
+    external_font: ExternalFont,
+    /// The last subset font face, kept until the text uses different characters.
+    #[serde(skip)]
+    font_subset: Option<FontSubset>,
 }
 
 /// This struct is provided as a result of requesting an [`SVG`] update based on a particular [`Configuration`].
 /// Fields are kept private as no modification of this is ever expected. It's just a convenient wrapper for serialisation.
 /// TODO: Possibly replace with untagged enum. Two variants. SVG and regular update.
-#[derive(Serialize)]
+#[derive(Serialize, Getters)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateResult {
     style: String,
@@ -130,6 +181,55 @@ pub struct UpdateResult {
     tasks_group: String,
     view_box: String,
     svg: Option<String>,
+    /// Issues found while applying the [`Configuration`], if any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub")]
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Checks that two [`ManycoreSystem`]s share their structure, i.e. they are runs of the same system:
+/// same size, same cores and same channels on each core.
+fn check_same_structure(
+    reference: &ManycoreSystem,
+    other: &ManycoreSystem,
+) -> Result<(), SVGError> {
+    let mismatch = |reason: String| SVGError::new(SVGErrorKind::ManycoreMismatch(reason));
+
+    if reference.rows() != other.rows() || reference.columns() != other.columns() {
+        return Err(mismatch(format!(
+            "Cannot combine a {}x{} system with a {}x{} one.",
+            reference.rows(),
+            reference.columns(),
+            other.rows(),
+            other.columns()
+        )));
+    }
+
+    // Same size systems only differ in the number of cores if one is missing some.
+    let reference_cores = reference.cores().list();
+    let other_cores = other.cores().list();
+    for i in 0..reference_cores.len().max(other_cores.len()) {
+        let same_core = match (reference_cores.get(i), other_cores.get(i)) {
+            (Some(reference_core), Some(other_core)) => {
+                reference_core.id() == other_core.id()
+                    && reference_core
+                        .channels()
+                        .channel()
+                        .keys()
+                        .eq(other_core.channels().channel().keys())
+            }
+            _ => false,
+        };
+
+        if !same_core {
+            return Err(mismatch(format!(
+                "Core {} differs in structure between the systems.",
+                i
+            )));
+        }
+    }
+
+    Ok(())
 }
 
 /// Error thrown when we can't get to the requested processing group.
@@ -147,6 +247,7 @@ impl SVG {
         height: CoordinateT,
         top_left: TopLeft,
         base_configuration: BaseConfiguration,
+        processed_base_configuration: ProcessedBaseConfiguration,
     ) -> Self {
         let view_box = ViewBox::new(width, height, &top_left);
         let number_of_cores = manycore.cores().list().len();
@@ -159,6 +260,10 @@ impl SVG {
             preserve_aspect_ratio: "xMidYMid meet",
             class: "mx-auto",
             view_box,
+            role: None,
+            aria_labelledby: None,
+            title: None,
+            desc: None,
             defs: Defs::new(&number_of_cores),
             style: Style::default(),
             root: Root {
@@ -168,6 +273,8 @@ impl SVG {
                 information_group: InformationGroup::new(&number_of_cores),
                 sinks_sources_group: SinksSourcesGroup::new(manycore.rows(), manycore.columns()),
                 tasks_group: TasksGroup::new(),
+                animation: None,
+                flow_highlight: None,
             },
             rows: *manycore.rows(),
             // columns,
@@ -175,7 +282,10 @@ impl SVG {
             base_view_box: view_box,
             borders_view_box: view_box,
             base_configuration,
-            processed_base_configuration: ProcessedBaseConfiguration::from(&base_configuration),
+            processed_base_configuration,
+            update_serialise_options: SerialiseOptions::compact(),
+            external_font: ExternalFont::default(),
+            font_subset: None,
         }
     }
 
@@ -193,6 +303,14 @@ impl SVG {
         self.height = self.height.saturating_add(bottom);
     }
 
+    /// Extends the [`SVG`]'s base and current viewBox to fit the provided [`Offsets`] and adjusts width and height accordingly.
+    fn fit_base_view_box(&mut self, offsets: &Offsets) {
+        self.base_view_box.fit_offsets(offsets);
+        self.view_box.restore_from(&self.base_view_box);
+        self.width = *self.base_view_box.width();
+        self.height = *self.base_view_box.height();
+    }
+
     /// Generates an [`UpdateResult`] based on a provided [`Configuration`], a possibly updated [`BaseConfiguration`] and a reference [`ManycoreSystem`].
     pub fn update_configurable_information(
         &mut self,
@@ -203,9 +321,46 @@ impl SVG {
         // Did the base configuration change? If so, we need to regenerate the whole SVG
         let has_new_base_config = *base_configuration != self.base_configuration;
         if has_new_base_config {
+            let update_serialise_options = self.update_serialise_options;
+            let external_font = std::mem::take(&mut self.external_font);
             *self = SVG::try_from_manycore_with_base_config(manycore, base_configuration)?;
+            self.update_serialise_options = update_serialise_options;
+            self.external_font = external_font;
         }
 
+        let diagnostics = self.apply_configuration(manycore, configuration)?;
+
+        // Subsetting depends on the text just generated.
+        self.apply_font_embedding()?;
+
+        Ok(UpdateResult {
+            style: self.style.stylesheet(),
+            information_group: self
+                .root
+                .information_group
+                .update_string(&self.update_serialise_options)?,
+            tasks_group: self
+                .root
+                .tasks_group
+                .update_string(&self.update_serialise_options)?,
+            view_box: String::from(&self.view_box),
+            // Include whole SVG if it's been updated. It will inherrently contain the updated data above
+            svg: if has_new_base_config {
+                Some(self.update_serialise_options.serialise_document(self)?)
+            } else {
+                None
+            },
+            diagnostics,
+        })
+    }
+
+    /// Generates the information layers, tasks, stylesheet and viewBox of the [`SVG`] from a [`Configuration`],
+    /// against its current geometry. Returns the [`Diagnostic`]s of the values that could not be displayed.
+    fn apply_configuration(
+        &mut self,
+        manycore: &mut ManycoreSystem,
+        configuration: &mut Configuration,
+    ) -> Result<Vec<Diagnostic>, SVGError> {
         let not_empty_configuration = !configuration.core_config().is_empty()
             || !configuration.router_config().is_empty()
             || !configuration.channel_config().is_empty()
@@ -229,19 +384,21 @@ impl SVG {
 
         // Clear information groups. Clear will keep memory allocated, hopefully less heap allocation penalties.
         self.root.information_group.groups_mut().clear();
+        self.root.information_group.set_legend(None);
+        self.root.information_group.set_communications(None);
         // Reset viewbox
         self.view_box.restore_from(&self.base_view_box);
 
         // Expand viewBox and adjust css if required (Sinks and Sources)
         // Always reset CSS. If user deselects all options and clicks apply, they expect the base render to show.
+        let mut show_border_routers = false;
         if let Some(border_routers_configuration) = configuration
             .channel_config_mut()
             .remove(BORDER_ROUTERS_KEY)
         {
             match border_routers_configuration {
-                FieldConfiguration::Boolean {
-                    value: show_border_routers,
-                } => {
+                FieldConfiguration::Boolean { value } => {
+                    show_border_routers = value;
                     if show_border_routers {
                         self.style = Style::base(); // CSS
 
@@ -265,7 +422,21 @@ impl SVG {
             self.style = Style::default(); // CSS
         }
 
+        // Theme CSS goes before any element specific rule.
+        self.style.apply_theme(configuration.theme());
+
         let mut offsets = Offsets::default();
+        let mut channel_offsets = Offsets::default();
+        let mut diagnostics = Vec::new();
+        // Edge link labels must not cover border routers, when shown.
+        let border_routers: Option<Vec<Offsets>> = show_border_routers.then(|| {
+            self.root
+                .sinks_sources_group
+                .g()
+                .iter()
+                .map(Offsets::from_sinksource)
+                .collect()
+        });
 
         // Should we update tasks too?
         let toggle_task = configuration
@@ -302,11 +473,13 @@ impl SVG {
                             configuration,
                             core,
                             links_with_load.as_ref(),
-                            self.style.css_mut(),
+                            self.style.element_rules_mut(),
                             processing_group,
                             &self.root.connections_group,
                             routing_configuration.as_ref(),
-                            &mut offsets,
+                            border_routers.as_deref(),
+                            &mut channel_offsets,
+                            &mut diagnostics,
                             &self.processed_base_configuration,
                         )?);
                 }
@@ -340,6 +513,12 @@ impl SVG {
             }
         }
 
+        // Channel labels may need moving apart, which changes how much room they need.
+        if *configuration.label_placement() {
+            channel_offsets = self.place_labels(show_border_routers, &mut diagnostics);
+        }
+        offsets.update(channel_offsets);
+
         // We need to do this separately here because the base viewBox might still need extending.
         // E.g. Border-routers -> off but no task toggle
         if !toggle_task {
@@ -348,21 +527,67 @@ impl SVG {
             });
         }
 
+        // Task graph overlay, if requested
+        if *configuration.task_graph() {
+            if let Some(communications) = CommunicationsOverlay::new(
+                manycore,
+                &self.root.processing_group,
+                &self.processed_base_configuration,
+            ) {
+                offsets.update(*communications.offsets());
+                self.root
+                    .information_group
+                    .set_communications(Some(communications));
+            }
+        }
+
         // Extend viewBox if required
         self.view_box.fit_offsets(&offsets);
 
-        Ok(UpdateResult {
-            style: self.style.css().clone(),
-            information_group: self.root.information_group.update_string()?,
-            tasks_group: self.root.tasks_group.update_string()?,
-            view_box: String::from(&self.view_box),
-            // Include whole SVG if it's been updated. It will inherrently contain the updated data above
-            svg: if has_new_base_config {
-                Some(quick_xml::se::to_string(self)?)
-            } else {
-                None
-            },
-        })
+        // The legend sits next to everything else, so it goes in last.
+        if *configuration.legend() {
+            if let Some(legend) = Legend::new(
+                configuration,
+                routing_configuration.as_ref(),
+                &self.view_box,
+                &self.processed_base_configuration,
+            )? {
+                self.view_box.fit_offsets(&legend.offsets());
+                self.root.information_group.set_legend(Some(legend));
+            }
+        }
+
+        Ok(diagnostics)
+    }
+
+    /// Highlights the routed path of a single flow, dimming everything else.
+    /// Dimension ordered algorithms give the flow's exact path. With [`RoutingAlgorithms::Observed`],
+    /// the flow is traced through the channels that carried observed traffic.
+    /// Returns the content of the highlight group as an update fragment.
+    pub fn highlight_flow(
+        &mut self,
+        manycore: &mut ManycoreSystem,
+        flow: &FlowSelection,
+        algorithm: &RoutingAlgorithms,
+    ) -> Result<String, SVGError> {
+        let flow_highlight = FlowHighlight::new(
+            manycore,
+            flow,
+            algorithm,
+            *self.base_configuration.topology(),
+            &self.root.processing_group,
+            &self.root.connections_group,
+        )?;
+        let fragment = flow_highlight.update_string(&self.update_serialise_options)?;
+
+        self.root.flow_highlight = Some(flow_highlight);
+
+        Ok(fragment)
+    }
+
+    /// Removes the flow highlight, if any.
+    pub fn clear_flow_highlight(&mut self) {
+        self.root.flow_highlight = None;
     }
 
     /// Adds a [`ClipPath`] to the [`SVG`]'s `<defs>`. Used in FreeForm exporting.
@@ -382,9 +607,45 @@ impl SVG {
 mod tests {
     use std::fs;
 
-    use manycore_parser::ManycoreSystem;
+    use manycore_parser::{Directions, ElementIDT, ManycoreSystem, RoutingAlgorithms};
+
+    use super::{
+        BaseConfiguration, Configuration, FlowSelection, SVGError, SerialiseOptions,
+        TextInformation, SVG,
+    };
+
+    /// Parses the ManyCore system shared by tests.
+    pub(crate) fn test_system() -> ManycoreSystem {
+        test_system_file("VisualiserOutput1.xml")
+    }
+
+    /// Parses a ManyCore system fixture from the `tests` folder.
+    pub(crate) fn test_system_file(file_name: &str) -> ManycoreSystem {
+        let path = format!("tests/{file_name}");
+
+        ManycoreSystem::parse_file(&path)
+            .unwrap_or_else(|error| panic!("Could not read input test file \"{path}\": {error:?}"))
+    }
+
+    /// Asserts that `result` failed because the provided systems don't share their structure.
+    pub(crate) fn assert_mismatch<T>(result: Result<T, SVGError>) {
+        match result {
+            Ok(_) => panic!("Systems with a different structure were combined."),
+            Err(error) => assert!(error
+                .to_string()
+                .starts_with("Mismatch with ManyCore System: ")),
+        }
+    }
 
-    use super::SVG;
+    /// Loads a [`Configuration`] fixture from the `tests` folder.
+    pub(crate) fn test_configuration(file_name: &str) -> Configuration {
+        let path = format!("tests/{file_name}");
+        let conf_file = fs::File::open(&path)
+            .unwrap_or_else(|error| panic!("Could not open \"{path}\": {error}"));
+
+        serde_json::from_reader(conf_file)
+            .unwrap_or_else(|error| panic!("Could not parse \"{path}\": {error}"))
+    }
 
     #[test]
     fn can_convert_from() {
@@ -405,4 +666,373 @@ mod tests {
         #[cfg(not(feature = "print"))]
         assert_eq!(res, expected);
     }
+
+    /// Routes `flow` in `manycore` and returns the `d` of every highlighted hop.
+    fn highlighted_hops(
+        svg: &mut SVG,
+        manycore: &mut ManycoreSystem,
+        flow: FlowSelection,
+        algorithm: RoutingAlgorithms,
+    ) -> Vec<String> {
+        let fragment = svg
+            .highlight_flow(manycore, &flow, &algorithm)
+            .expect("Could not highlight flow.");
+        assert!(fragment.starts_with("<style>"));
+
+        fragment
+            .split("<path d=\"")
+            .skip(1)
+            .map(|path| path[..path.find('"').expect("Unterminated d")].to_string())
+            .collect()
+    }
+
+    /// Returns the `d` of the link leaving `core_id` towards `direction`.
+    fn output_link(svg: &SVG, core_id: ElementIDT, direction: Directions) -> String {
+        svg.root
+            .connections_group
+            .get_output_connection(&core_id, direction)
+            .expect("Missing output link")
+            .d()
+            .clone()
+    }
+
+    #[test]
+    fn can_highlight_flow() {
+        let mut manycore = test_system();
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+
+        // Task 3 on core 1 sends to task 4 on core 5: row first goes South, then East.
+        assert_eq!(
+            highlighted_hops(
+                &mut svg,
+                &mut manycore,
+                FlowSelection::TaskEdge { from: 3, to: 4 },
+                RoutingAlgorithms::RowFirst,
+            ),
+            [
+                output_link(&svg, 1, Directions::South),
+                output_link(&svg, 4, Directions::East),
+            ]
+        );
+
+        // Column first routes the same flow East, then South.
+        assert_eq!(
+            highlighted_hops(
+                &mut svg,
+                &mut manycore,
+                FlowSelection::Cores {
+                    source: 1,
+                    destination: 5,
+                },
+                RoutingAlgorithms::ColumnFirst,
+            ),
+            [
+                output_link(&svg, 1, Directions::East),
+                output_link(&svg, 2, Directions::South),
+            ]
+        );
+
+        // Pairs carrying no routed traffic still have a path: North to row 0, then West.
+        assert_eq!(
+            highlighted_hops(
+                &mut svg,
+                &mut manycore,
+                FlowSelection::Cores {
+                    source: 8,
+                    destination: 0,
+                },
+                RoutingAlgorithms::RowFirst,
+            ),
+            [
+                output_link(&svg, 8, Directions::North),
+                output_link(&svg, 5, Directions::North),
+                output_link(&svg, 2, Directions::West),
+                output_link(&svg, 1, Directions::West),
+            ]
+        );
+
+        let missing = svg.highlight_flow(
+            &mut manycore,
+            &FlowSelection::Cores {
+                source: 8,
+                destination: 9,
+            },
+            &RoutingAlgorithms::RowFirst,
+        );
+        assert!(missing.is_err());
+
+        svg.clear_flow_highlight();
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+        assert!(!res.contains("flowHighlight"));
+    }
+
+    #[test]
+    fn can_highlight_flow_through_wrap_links() {
+        let mut manycore = test_system();
+        let base_configuration: BaseConfiguration = serde_json::from_str(
+            r#"{"attribute_font_size": 16.0, "task_font_size": 22.0, "topology": "Torus"}"#,
+        )
+        .expect("Could not parse torus base configuration");
+        let mut svg = SVG::try_from_manycore_with_base_config(&manycore, &base_configuration)
+            .expect("Could not convert Manycorer to SVG.");
+
+        // Task 2 on core 7 sends to task 3 on core 1. Every channel carries observed traffic,
+        // so the flow takes the South wrap-around link rather than going North twice.
+        assert_eq!(
+            highlighted_hops(
+                &mut svg,
+                &mut manycore,
+                FlowSelection::TaskEdge { from: 2, to: 3 },
+                RoutingAlgorithms::Observed,
+            ),
+            [output_link(&svg, 7, Directions::South)]
+        );
+    }
+
+    #[test]
+    fn can_animate_time_series() {
+        let configuration = test_configuration("conf11.json");
+
+        // Core 0 gets younger, Task 3 more expensive and Task 4 moves from core 5 to core 8.
+        let mut manycores = vec![test_system(), test_system_file("VisualiserOutput2.xml")];
+        let svg = SVG::try_animate(
+            &mut manycores,
+            &configuration,
+            &BaseConfiguration::default(),
+            500,
+        )
+        .expect("Could not generate animation.");
+
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+        let frame_start = |id: &str| {
+            res.find(&format!("<g id=\"{id}\""))
+                .unwrap_or_else(|| panic!("Missing {id}"))
+        };
+        let (frame0, frame1) =
+            res[frame_start("frame0")..].split_at(frame_start("frame1") - frame_start("frame0"));
+
+        assert!(res.contains("<set id=\"animationClock\" attributeName=\"visibility\" to=\"visible\" begin=\"0ms;animationClock.end\" dur=\"1000ms\"/>"));
+        // Element rules move from the stylesheet into the frames.
+        assert!(!res.contains("#c0 {fill:"));
+        assert!(frame0.contains("<set href=\"#c0\" attributeName=\"fill\" to=\"#f97316\" begin=\"animationClock.begin+0ms\" dur=\"500ms\"/>"));
+        assert!(frame1.contains("<set href=\"#c0\" attributeName=\"fill\" to=\"#eab308\" begin=\"animationClock.begin+500ms\" dur=\"500ms\"/>"));
+
+        // Tasks and their costs belong to each frame.
+        assert!(frame0.contains("<g id=\"frame0Tasks\""));
+        assert!(frame0.contains(">[80]<"));
+        assert!(frame1.contains(">[95]<"));
+        assert_eq!(res.matches(">T4<").count(), 2);
+
+        let task_4_y = |frame: &str| -> i32 {
+            let text_start = frame[..frame.find(">T4<").expect("Missing Task 4")]
+                .rfind("<text ")
+                .expect("Missing Task 4 text");
+            let y = &frame[text_start..]["<text ".len()..];
+            let y = &y[y.find("y=\"").expect("Missing y") + 3..];
+
+            y[..y.find('"').expect("Unterminated y")]
+                .parse()
+                .expect("Invalid y")
+        };
+        // One row further down.
+        assert_eq!(task_4_y(frame1) - task_4_y(frame0), 414);
+    }
+
+    #[test]
+    fn cannot_animate_different_systems() {
+        let configuration = test_configuration("conf11.json");
+
+        let mut manycores = vec![test_system(), test_system_file("VisualiserOutput3.xml")];
+        assert_mismatch(SVG::try_animate(
+            &mut manycores,
+            &configuration,
+            &BaseConfiguration::default(),
+            500,
+        ));
+    }
+
+    #[test]
+    fn can_serialise_with_options() {
+        let manycore = test_system();
+
+        let svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+
+        let mut options = SerialiseOptions::compact();
+        options.set_xml_declaration(true);
+
+        let compact = String::from_utf8(svg.serialise(&options).expect("Could not serialise SVG."))
+            .expect("Serialised SVG is not UTF-8.");
+        assert!(compact.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?><svg "));
+        assert!(compact.contains("><defs><marker "));
+
+        options.set_gzip(true);
+        let svgz = svg.serialise(&options).expect("Could not serialise SVGZ.");
+        assert!(svgz.starts_with(&[0x1f, 0x8b]));
+    }
+
+    #[test]
+    fn can_round_font_sizes() {
+        let svg = SVG::try_from_manycore_with_base_config(
+            &test_system(),
+            &BaseConfiguration::new(16.0, 22.37),
+        )
+        .expect("Could not convert Manycore to SVG.");
+
+        let font_size = |precision: Option<u8>| {
+            let mut options = SerialiseOptions::compact();
+            options.set_precision(precision);
+            let res = String::from_utf8(svg.serialise(&options).expect("Could not serialise SVG."))
+                .expect("Serialised SVG is not UTF-8.");
+
+            let start = res.find("font-size=\"").expect("Missing font size") + 11;
+            res[start..start + res[start..].find('"').expect("Unterminated font size")].to_string()
+        };
+
+        // Whole pixels unless a precision is requested, trailing zeros are dropped.
+        assert_eq!(font_size(None), "22px");
+        assert_eq!(font_size(Some(2)), "22.37px");
+        assert_eq!(font_size(Some(1)), "22.4px");
+        assert_eq!(font_size(Some(4)), "22.37px");
+        assert_eq!(font_size(Some(0)), "22px");
+    }
+
+    #[test]
+    fn can_measure_text_from_glyph_metrics() {
+        let ascii = TextInformation::calculate_length_util(16.0, "Load", None);
+
+        // Characters are counted, not bytes.
+        assert_eq!(
+            TextInformation::calculate_length_util(16.0, "Lödé", None),
+            ascii
+        );
+        // Wide characters take a full em.
+        assert_eq!(
+            TextInformation::calculate_length_util(16.0, "負荷", None),
+            32
+        );
+        // Padding is expressed in characters.
+        assert_eq!(
+            TextInformation::calculate_length_util(16.0, "Lo", Some(2.0)),
+            ascii
+        );
+    }
+
+    /// Generates the test system [`SVG`] with the provided [`Theme`].
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    fn themed_svg(theme: super::Theme) -> SVG {
+        let mut manycore = test_system();
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycore to SVG.");
+
+        let mut configuration = Configuration::default();
+        *configuration.theme_mut() = theme;
+        svg.update_configurable_information(
+            &mut manycore,
+            &mut configuration,
+            &BaseConfiguration::default(),
+        )
+        .expect("Could not generate SVG update");
+
+        svg
+    }
+
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    #[test]
+    fn can_resolve_theme_for_export() {
+        let svg = themed_svg(super::Theme::Dark);
+        let document = svg.export_document().expect("Could not export SVG.");
+
+        assert!(!document.contains("var(--"));
+        assert!(document.contains(".themeText {fill: #f5f5f5;}"));
+        let super::ViewBox {
+            x,
+            y,
+            width,
+            height,
+        } = *svg.view_box();
+        assert!(document.contains(&format!(
+            "><rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"#171717\"/>"
+        )));
+
+        // The light theme has no CSS to resolve, nor background.
+        let svg = themed_svg(super::Theme::Light);
+        assert_eq!(
+            svg.export_document().expect("Could not export SVG."),
+            String::try_from(&svg).expect("Could not convert from SVG to string")
+        );
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn can_rasterise_to_png() {
+        use super::{CoordinateT, RasterSize};
+        use resvg::tiny_skia::Pixmap;
+
+        let svg = themed_svg(super::Theme::Dark);
+        let view_box = *svg.view_box();
+        let rasterise = |size: RasterSize| {
+            Pixmap::decode_png(&svg.try_to_png(size).expect("Could not rasterise SVG."))
+                .expect("Could not decode PNG.")
+        };
+
+        // The SVG fits the requested size, preserving its aspect ratio.
+        let pixmap = rasterise(RasterSize::Pixels {
+            width: 800,
+            height: 800,
+        });
+        let scale = (800.0 / view_box.width as f32).min(800.0 / view_box.height as f32);
+        assert_eq!(
+            (pixmap.width(), pixmap.height()),
+            (
+                (view_box.width as f32 * scale).round() as u32,
+                (view_box.height as f32 * scale).round() as u32
+            )
+        );
+        assert!(pixmap.width() == 800 || pixmap.height() == 800);
+
+        // Twice the SVG units at 192 DPI.
+        let pixmap = rasterise(RasterSize::Dpi(192.0));
+        assert_eq!(
+            (pixmap.width(), pixmap.height()),
+            (view_box.width as u32 * 2, view_box.height as u32 * 2)
+        );
+
+        // Theme colours are drawn, background included.
+        let colour_at = |x: CoordinateT, y: CoordinateT| {
+            let pixel = pixmap
+                .pixel(((x - view_box.x) * 2) as u32, ((y - view_box.y) * 2) as u32)
+                .expect("Pixel out of the PNG");
+
+            (pixel.red(), pixel.green(), pixel.blue(), pixel.alpha())
+        };
+        assert_eq!(
+            colour_at(view_box.x + 1, view_box.y + 1),
+            (0x17, 0x17, 0x17, 255)
+        );
+        // Inside core 0.
+        assert_eq!(colour_at(-490, -340), (0x40, 0x40, 0x40, 255));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn can_export_to_pdf() {
+        let svg = themed_svg(super::Theme::Dark);
+        let pdf = svg.try_to_pdf().expect("Could not export SVG to PDF.");
+
+        assert!(pdf.starts_with(b"%PDF"));
+        // One page, as big as the viewBox.
+        let view_box = svg.view_box();
+        let media_box = format!("/MediaBox [0 0 {} {}]", view_box.width, view_box.height);
+        assert_eq!(
+            pdf.windows(media_box.len())
+                .filter(|window| *window == media_box.as_bytes())
+                .count(),
+            1
+        );
+    }
 }
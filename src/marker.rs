@@ -3,13 +3,14 @@
 use const_format::concatcp;
 use serde::Serialize;
 
-use crate::{CommonAttributes, CoordinateT};
+use crate::{CommonAttributes, CoordinateT, LIGHT_COLOURS, MARKER_CLASS_NAME, STROKE_CLASS_NAME};
 
 pub(crate) static MARKER_PATH: &str = "M0,0 M0,0 V14 L14,7 Z";
 pub(crate) static MARKER_REFERENCE: &str = "url(#arrowHead)";
 pub(crate) const MARKER_HEIGHT: CoordinateT = 14;
 static MARKER_DIMEN: &'static str = concatcp!(MARKER_HEIGHT);
 static MARKER_REF_Y: &'static str = concatcp!(MARKER_HEIGHT.saturating_div(2));
+static MARKER_PATH_CLASS_NAME: &'static str = concatcp!(MARKER_CLASS_NAME, " ", STROKE_CLASS_NAME);
 
 /// Object representation of the marker path (arow).
 #[derive(Serialize)]
@@ -26,8 +27,8 @@ impl Default for MarkerPath {
     fn default() -> Self {
         Self {
             d: MARKER_PATH,
-            fill: "black",
-            attributes: CommonAttributes::with_no_class(),
+            fill: LIGHT_COLOURS.marker_fill,
+            attributes: CommonAttributes::with_class(MARKER_PATH_CLASS_NAME),
         }
     }
 }
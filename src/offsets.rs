@@ -10,7 +10,7 @@ use crate::{
         SinkSource, SINKS_SOURCES_SHORT_SIDE_LENGTH, SINKS_SOURCES_STROKE_WIDTH,
     },
     tasks_group::Task,
-    CoordinateT, SVGError, TextInformation, CHAR_V_PADDING,
+    CoordinateT, TextInformation, CHAR_V_PADDING,
 };
 
 /// Helper struct to calculate viewBox offsets.
@@ -39,6 +39,14 @@ impl Offsets {
         }
     }
 
+    /// Whether two [`Offsets`] instances, seen as boxes, overlap.
+    pub(crate) fn overlaps(&self, other: &Offsets) -> bool {
+        self.left < other.right
+            && other.left < self.right
+            && self.top < other.bottom
+            && other.top < self.bottom
+    }
+
     /// Updates an [`Offsets`] instance by comparring it to another to maximise viewBox size.
     pub(crate) fn update(&mut self, other: Offsets) {
         self.left = min(self.left, other.left);
@@ -48,10 +56,7 @@ impl Offsets {
     }
 
     /// Utility to generate channel text offset from a [`TextInformation`] instance.
-    pub(crate) fn try_from_channel(
-        value: &TextInformation,
-        direction: &Directions,
-    ) -> Result<Self, SVGError> {
+    pub(crate) fn from_channel(value: &TextInformation, direction: &Directions) -> Self {
         // East link is the only one that affects top
         let top = match direction {
             Directions::East => value
@@ -63,23 +68,23 @@ impl Offsets {
         // For left and right we only care about South and North directions respectively.
         // Remaining directions wouldn't affect viewBox.
         let left = match direction {
-            Directions::South => value.x().saturating_sub(value.calculate_length(None)?),
+            Directions::South => value.x().saturating_sub(value.calculate_length(None)),
             _ => *value.x(),
         };
 
         let right = match direction {
-            Directions::North => value.x().saturating_add(value.calculate_length(None)?),
+            Directions::North => value.x().saturating_add(value.calculate_length(None)),
             _ => *value.x(),
         };
 
-        Ok(Offsets::new(
+        Offsets::new(
             left,
             top,
             right,
             value
                 .y()
                 .saturating_add(*value.font_size().px() as CoordinateT),
-        ))
+        )
     }
 
     /// Utility to generate viewBox offset from a [`SinkSource`] instance.
@@ -1,7 +1,24 @@
+use std::fmt;
+
 use quick_xml::DeError;
 
+use crate::SerialiseOptions;
+
 pub(crate) trait PartialUpdate {
-    /// Generates a String to include in an SVG update by serialising [`Self`].
+    /// Writes [`Self`] into `writer` according to the provided [`SerialiseOptions`], to include in an SVG update.
+    /// It writes the serialised [`Self`] without the main `<g>`.
+    fn write_update<W: fmt::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), DeError>;
+
+    /// Generates a String to include in an SVG update by serialising [`Self`] according to the provided [`SerialiseOptions`].
     /// It returns the serialised [`Self`] without the main `<g>`.
-    fn update_string(&self) -> Result<String, DeError>;
+    fn update_string(&self, options: &SerialiseOptions) -> Result<String, DeError> {
+        let mut buf = String::new();
+        self.write_update(&mut buf, options)?;
+
+        Ok(buf)
+    }
 }
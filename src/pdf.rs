@@ -0,0 +1,28 @@
+use svg2pdf::usvg::{
+    fontdb::Database, Options, PostProcessingSteps, Tree, TreeParsing, TreePostProc,
+};
+
+use crate::{export_error, style::roboto_mono_font, SVGError, SVG};
+
+#[cfg(doc)]
+use crate::ViewBox;
+
+impl SVG {
+    /// Exports the [`SVG`] as a single page vector PDF. The page size follows the current [`ViewBox`].
+    /// Text is drawn with the embedded Roboto Mono and CSS fills, theme colours included, are preserved.
+    pub fn try_to_pdf(&self) -> Result<Vec<u8>, SVGError> {
+        let options = Options {
+            font_family: String::from("Roboto Mono"),
+            ..Options::default()
+        };
+        let mut tree = Tree::from_str(&self.export_document()?, &options)
+            .map_err(|error| export_error(format!("Could not parse SVG: {error}")))?;
+
+        // Only Roboto Mono is loaded, so the output does not depend on the machine it's generated on.
+        let mut fontdb = Database::new();
+        fontdb.load_font_data(roboto_mono_font()?);
+        tree.postprocess(PostProcessingSteps::default(), &fontdb);
+
+        Ok(svg2pdf::convert_tree(&tree, svg2pdf::Options::default()))
+    }
+}
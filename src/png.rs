@@ -0,0 +1,59 @@
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{fontdb::Database, Options, PostProcessingSteps, Tree, TreeParsing, TreePostProc},
+};
+
+use crate::{export_error, style::roboto_mono_font, SVGError, SVG};
+
+/// SVG user units are CSS pixels, which are defined at 96 DPI.
+static SVG_DPI: f32 = 96.0;
+
+/// Requested size of a rasterised [`SVG`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RasterSize {
+    /// Scale the [`SVG`] to the given dots per inch. 96 DPI renders one pixel per SVG unit.
+    Dpi(f32),
+    /// Fit the [`SVG`] within the given pixel size, preserving its aspect ratio.
+    Pixels { width: u32, height: u32 },
+}
+
+impl SVG {
+    /// Rasterises the [`SVG`] to PNG bytes at the requested [`RasterSize`].
+    pub fn try_to_png(&self, size: RasterSize) -> Result<Vec<u8>, SVGError> {
+        let options = Options {
+            font_family: String::from("Roboto Mono"),
+            ..Options::default()
+        };
+        let mut tree = Tree::from_str(&self.export_document()?, &options)
+            .map_err(|error| export_error(format!("Could not parse SVG: {error}")))?;
+
+        // Only Roboto Mono is loaded, so the output does not depend on the machine it's generated on.
+        let mut fontdb = Database::new();
+        fontdb.load_font_data(roboto_mono_font()?);
+        tree.postprocess(PostProcessingSteps::default(), &fontdb);
+
+        let (svg_width, svg_height) = (tree.size.width(), tree.size.height());
+        let scale = match size {
+            RasterSize::Dpi(dpi) => dpi / SVG_DPI,
+            RasterSize::Pixels { width, height } => {
+                (width as f32 / svg_width).min(height as f32 / svg_height)
+            }
+        };
+
+        let mut pixmap = Pixmap::new(
+            (svg_width * scale).round() as u32,
+            (svg_height * scale).round() as u32,
+        )
+        .ok_or_else(|| export_error(format!("Invalid PNG size at scale {scale}.")))?;
+
+        resvg::render(
+            &tree,
+            Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+
+        pixmap
+            .encode_png()
+            .map_err(|error| export_error(format!("Could not encode PNG: {error}")))
+    }
+}
@@ -4,59 +4,29 @@ use manycore_parser::ElementIDT;
 use serde::Serialize;
 
 use crate::{
-    style::BASE_FILL_CLASS_NAME, ClipPath, CoordinateT, SVGError, TopLeft, CONNECTION_LENGTH,
-    MARKER_HEIGHT, USE_FREEFORM_CLIP_PATH,
+    style::BASE_FILL_CLASS_NAME, ClipPath, CoordinateT, DataAttributes, GeometryConfiguration,
+    GroupLabel, SVGError, Tooltip, TopLeft, LIGHT_COLOURS, STROKE_CLASS_NAME,
+    USE_FREEFORM_CLIP_PATH,
 };
 
-pub(crate) const SIDE_LENGTH: CoordinateT = 100;
-pub(crate) const ROUTER_OFFSET: CoordinateT = SIDE_LENGTH.saturating_div(4).saturating_mul(3);
-pub(crate) static BLOCK_LENGTH: CoordinateT = SIDE_LENGTH + ROUTER_OFFSET;
-pub(crate) static HALF_SIDE_LENGTH: CoordinateT = SIDE_LENGTH.saturating_div(2);
-pub(crate) static HALF_ROUTER_OFFSET: CoordinateT = ROUTER_OFFSET.saturating_div(2);
-pub(crate) static BLOCK_DISTANCE: CoordinateT = CONNECTION_LENGTH
-    .saturating_sub(ROUTER_OFFSET)
-    .saturating_add(MARKER_HEIGHT);
-
-// Example after concatenation with SIDE_LENGTH = 100 -> ROUTER_OFFSET = 75
-// l0,100 l100,0 l0,-75 l-25,-25 l-75,0 Z
-static PROCESSOR_PATH: &'static str = concatcp!(
-    "l0,",
-    SIDE_LENGTH,
-    " l",
-    SIDE_LENGTH,
-    ",0 l0,-",
-    ROUTER_OFFSET,
-    " l-",
-    SIDE_LENGTH - ROUTER_OFFSET,
-    ",-",
-    SIDE_LENGTH - ROUTER_OFFSET,
-    " l-",
-    ROUTER_OFFSET,
-    ",0 Z"
-);
-
-// Example after concatenation with SIDE_LENGTH = 100 -> ROUTER_OFFSET = 75
-// l0,-75 l100,0 l0,100 l-75,0 Z
-static ROUTER_PATH: &'static str = concatcp!(
-    "l0,-",
-    ROUTER_OFFSET,
-    " l",
-    SIDE_LENGTH,
-    ",0 l0,",
-    SIDE_LENGTH,
-    " l-",
-    ROUTER_OFFSET,
-    ",0 Z"
-);
+pub(crate) const DEFAULT_SIDE_LENGTH: CoordinateT = 100;
+pub(crate) static MINIMUM_SIDE_LENGTH: CoordinateT = 60;
+pub(crate) static MAXIMUM_SIDE_LENGTH: CoordinateT = 250;
+pub(crate) const DEFAULT_ROUTER_OFFSET: CoordinateT =
+    DEFAULT_SIDE_LENGTH.saturating_div(4).saturating_mul(3);
+pub(crate) static MINIMUM_ROUTER_OFFSET: CoordinateT = 30;
+pub(crate) static MAXIMUM_ROUTER_OFFSET: CoordinateT = 200;
 
 pub(crate) const CORE_ROUTER_STROKE_WIDTH: CoordinateT = 1;
 pub(crate) static CORE_ROUTER_STROKE_WIDTH_STR: &'static str = concatcp!(CORE_ROUTER_STROKE_WIDTH);
+pub(crate) const BASE_FILL_STROKE_CLASS_NAME: &'static str =
+    concatcp!(BASE_FILL_CLASS_NAME, " ", STROKE_CLASS_NAME);
 
 /// Wrapper around attributes shared by different elements.
 #[derive(Serialize, Setters, Debug)]
 pub(crate) struct CommonAttributes {
-    #[serde(rename = "@class", skip_serializing_if = "Option::is_none")]
-    class: Option<&'static str>,
+    #[serde(rename = "@class")]
+    class: &'static str,
     #[serde(rename = "@fill-rule")]
     fill_rule: &'static str,
     #[serde(rename = "@stroke")]
@@ -70,9 +40,9 @@ pub(crate) struct CommonAttributes {
 impl Default for CommonAttributes {
     fn default() -> Self {
         Self {
-            class: Some(BASE_FILL_CLASS_NAME),
+            class: BASE_FILL_STROKE_CLASS_NAME,
             fill_rule: "evenodd",
-            stroke: "black",
+            stroke: LIGHT_COLOURS.stroke,
             stroke_linecap: "butt",
             stroke_width: CORE_ROUTER_STROKE_WIDTH_STR,
         }
@@ -80,12 +50,12 @@ impl Default for CommonAttributes {
 }
 
 impl CommonAttributes {
-    /// Generates  a [`CommonAttributes`] instance with no class.
-    pub(crate) fn with_no_class() -> Self {
+    /// Generates a [`CommonAttributes`] instance with the given class.
+    pub(crate) fn with_class(class: &'static str) -> Self {
         Self {
-            class: None,
+            class,
             fill_rule: "evenodd",
-            stroke: "black",
+            stroke: LIGHT_COLOURS.stroke,
             stroke_linecap: "butt",
             stroke_width: CORE_ROUTER_STROKE_WIDTH_STR,
         }
@@ -93,7 +63,7 @@ impl CommonAttributes {
 }
 
 /// Object representattion of the SVG `<path>` that makes up a router.
-#[derive(Serialize, MutGetters, Getters)]
+#[derive(Serialize, MutGetters, Getters, Setters)]
 pub(crate) struct Router {
     /// Router coordinates, (x, y)
     #[serde(skip)]
@@ -101,11 +71,16 @@ pub(crate) struct Router {
     move_coordinates: (CoordinateT, CoordinateT),
     #[serde(rename = "@id")]
     id: String,
+    #[serde(flatten)]
+    data: DataAttributes,
     #[serde(rename = "@d")]
     d: String,
     #[serde(flatten)]
     #[getset(get_mut = "pub")]
     attributes: CommonAttributes,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    tooltip: Option<Tooltip>,
 }
 
 impl Router {
@@ -115,14 +90,17 @@ impl Router {
         column: &CoordinateT,
         id: &ElementIDT,
         top_left: &TopLeft,
+        geometry: &GeometryConfiguration,
     ) -> Self {
-        let (move_x, move_y) = Self::get_move_coordinates(row, column, top_left);
+        let (move_x, move_y) = Self::get_move_coordinates(row, column, top_left, geometry);
 
         Self {
             move_coordinates: (move_x, move_y),
             id: format!("r{}", id),
-            d: format!("M{},{} {}", move_x, move_y, ROUTER_PATH),
+            data: DataAttributes::router(id, row, column),
+            d: format!("M{},{} {}", move_x, move_y, geometry.router_path()),
             attributes: CommonAttributes::default(),
+            tooltip: None,
         }
     }
 
@@ -131,15 +109,16 @@ impl Router {
         row: &CoordinateT,
         column: &CoordinateT,
         top_left: &TopLeft,
+        geometry: &GeometryConfiguration,
     ) -> (CoordinateT, CoordinateT) {
-        let move_x = (column * BLOCK_LENGTH)
-            + ROUTER_OFFSET
-            + column * BLOCK_DISTANCE
+        let move_x = (column * geometry.block_length())
+            + geometry.router_offset()
+            + column * geometry.block_distance()
             + top_left.x()
             + CORE_ROUTER_STROKE_WIDTH;
-        let move_y = row * BLOCK_LENGTH
-            + ROUTER_OFFSET
-            + row * BLOCK_DISTANCE
+        let move_y = row * geometry.block_length()
+            + geometry.router_offset()
+            + row * geometry.block_distance()
             + top_left.y()
             + CORE_ROUTER_STROKE_WIDTH;
 
@@ -148,7 +127,7 @@ impl Router {
 }
 
 /// Object representattion of the SVG `<path>` that makes up a core.
-#[derive(Serialize, MutGetters, Getters)]
+#[derive(Serialize, MutGetters, Getters, Setters)]
 pub struct Core {
     /// Core coordinates, (x, y)
     #[serde(skip)]
@@ -156,11 +135,16 @@ pub struct Core {
     move_coordinates: (CoordinateT, CoordinateT),
     #[serde(rename = "@id")]
     id: String,
+    #[serde(flatten)]
+    data: DataAttributes,
     #[serde(rename = "@d")]
     d: String,
     #[serde(flatten)]
     #[getset(get_mut = "pub")]
     attributes: CommonAttributes,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    tooltip: Option<Tooltip>,
 }
 
 impl Core {
@@ -169,14 +153,15 @@ impl Core {
         row: &CoordinateT,
         column: &CoordinateT,
         top_left: &TopLeft,
+        geometry: &GeometryConfiguration,
     ) -> (CoordinateT, CoordinateT) {
-        let move_x = column * BLOCK_LENGTH
-            + column * BLOCK_DISTANCE
+        let move_x = column * geometry.block_length()
+            + column * geometry.block_distance()
             + top_left.x()
             + CORE_ROUTER_STROKE_WIDTH;
-        let move_y = row * BLOCK_LENGTH
-            + ROUTER_OFFSET
-            + row * BLOCK_DISTANCE
+        let move_y = row * geometry.block_length()
+            + geometry.router_offset()
+            + row * geometry.block_distance()
             + top_left.y()
             + CORE_ROUTER_STROKE_WIDTH;
 
@@ -184,14 +169,22 @@ impl Core {
     }
 
     /// Generates a new [`Core`] instance from the given parameters.
-    fn new(row: &CoordinateT, column: &CoordinateT, id: &ElementIDT, top_left: &TopLeft) -> Self {
-        let (move_x, move_y) = Self::get_move_coordinates(row, column, top_left);
+    fn new(
+        row: &CoordinateT,
+        column: &CoordinateT,
+        id: &ElementIDT,
+        top_left: &TopLeft,
+        geometry: &GeometryConfiguration,
+    ) -> Self {
+        let (move_x, move_y) = Self::get_move_coordinates(row, column, top_left, geometry);
 
         Self {
             move_coordinates: (move_x, move_y),
             id: format!("c{}", id),
-            d: format!("M{},{} {}", move_x, move_y, PROCESSOR_PATH),
+            data: DataAttributes::core(id, row, column),
+            d: format!("M{},{} {}", move_x, move_y, geometry.processor_path()),
             attributes: CommonAttributes::default(),
+            tooltip: None,
         }
     }
 }
@@ -206,10 +199,10 @@ pub(crate) struct ProcessingGroup {
     #[serde(rename = "@id")]
     id: ElementIDT,
     #[serde(rename = "path")]
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub")]
     core: Core,
     #[serde(rename = "path")]
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub")]
     router: Router,
 }
 
@@ -221,19 +214,20 @@ impl ProcessingGroup {
         id: &ElementIDT,
         top_left: &TopLeft,
         clip_paths: &mut Vec<ClipPath>,
+        geometry: &GeometryConfiguration,
     ) -> Result<Self, SVGError> {
         // Core
-        let core = Core::new(row, column, id, top_left);
+        let core = Core::new(row, column, id, top_left, geometry);
         let (core_x, core_y) = core.move_coordinates;
         // Core clip path
-        let core_clip = ClipPath::for_core(*id, core_x, core_y);
+        let core_clip = ClipPath::for_core(*id, core_x, core_y, geometry);
         clip_paths.push(core_clip);
 
         // Router
-        let router = Router::new(row, column, id, top_left);
+        let router = Router::new(row, column, id, top_left, geometry);
         let (router_x, router_y) = router.move_coordinates;
         // Router clip path
-        let router_clip = ClipPath::for_router(*id, router_x, router_y);
+        let router_clip = ClipPath::for_router(*id, router_x, router_y, geometry);
         clip_paths.push(router_clip);
 
         Ok(Self {
@@ -246,13 +240,16 @@ impl ProcessingGroup {
 }
 
 /// An SVG `<g>` that wraps all [`ProcessingGroup`] instances.
-#[derive(Serialize, MutGetters, Getters)]
+#[derive(Serialize, MutGetters, Getters, Setters)]
 #[getset(get_mut = "pub", get = "pub")]
 pub(crate) struct ProcessingParentGroup {
     #[serde(rename = "@id")]
     id: &'static str,
     #[serde(rename = "@clip-path")]
     clip_path: &'static str,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    label: Option<GroupLabel>,
     g: Vec<ProcessingGroup>,
 }
 
@@ -263,6 +260,7 @@ impl ProcessingParentGroup {
             id: "processingGroup",
             g: Vec::with_capacity(*number_of_cores),
             clip_path: USE_FREEFORM_CLIP_PATH,
+            label: None,
         }
     }
 }
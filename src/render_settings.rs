@@ -8,8 +8,11 @@ use manycore_parser::ElementIDT;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    tasks_group::DEFAULT_TASK_FONT_SIZE, CoordinateT, FontSizeT, CHAR_V_PADDING,
-    DEFAULT_ATTRIBUTE_FONT_SIZE,
+    sinks_sources_layer::SinksSourcesGeometry, tasks_group::DEFAULT_TASK_FONT_SIZE, CoordinateT,
+    FontSizeT, Theme, CHAR_V_PADDING, DEFAULT_ATTRIBUTE_FONT_SIZE, DEFAULT_CONNECTION_LENGTH,
+    DEFAULT_ROUTER_OFFSET, DEFAULT_SIDE_LENGTH, MARKER_HEIGHT, MAXIMUM_CONNECTION_LENGTH,
+    MAXIMUM_ROUTER_OFFSET, MAXIMUM_SIDE_LENGTH, MINIMUM_CONNECTION_LENGTH, MINIMUM_ROUTER_OFFSET,
+    MINIMUM_SIDE_LENGTH,
 };
 
 mod configurable_base_configuration;
@@ -18,6 +21,8 @@ mod field_configuration;
 pub use configurable_base_configuration::*;
 pub use field_configuration::*;
 
+#[cfg(doc)]
+use crate::SVG;
 #[cfg(doc)]
 use manycore_parser::{Channel, Core, Router};
 
@@ -25,7 +30,13 @@ use manycore_parser::{Channel, Core, Router};
 /// * `core_config`: A [`BTreeMap`] with [`String`] attribute key and [`FieldConfiguration`] value. Controls what [`Core`] information to display and how.
 /// * `router_config`: A [`BTreeMap`] with [`String`] attribute key and [`FieldConfiguration`] value. Controls what [`Router`] information to display and how.
 /// * `channel_config`: A [`BTreeMap`] with [`String`] attribute key and [`FieldConfiguration`] value. Controls what [`Channel`] information to display and how.
-#[derive(Serialize, Deserialize, Getters, MutGetters, Default, PartialEq, Debug)]
+/// * `theme`: The colour [`Theme`] to render with.
+/// * `legend`: Whether to render a legend for every active colour scale.
+/// * `task_graph`: Whether to overlay the task graph communications between allocated cores.
+/// * `label_placement`: Whether to move channel labels that overlap each other or border routers.
+/// * `channel_attributes`: Ordered list of the [`ChannelAttribute`]s to display next to each link.
+///   If empty, the first two `channel_config` keys are displayed, in alphabetical order.
+#[derive(Serialize, Deserialize, Getters, MutGetters, Default, PartialEq, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[getset(get = "pub", get_mut = "pub")]
 pub struct Configuration {
@@ -34,16 +45,120 @@ pub struct Configuration {
     channel_config: BTreeMap<String, FieldConfiguration>,
     core_fills: BTreeMap<ElementIDT, String>,
     router_fills: BTreeMap<ElementIDT, String>,
+    #[serde(default)]
+    theme: Theme,
+    #[serde(default)]
+    legend: bool,
+    #[serde(default)]
+    task_graph: bool,
+    #[serde(default)]
+    label_placement: bool,
+    #[serde(default)]
+    channel_attributes: Vec<ChannelAttribute>,
+}
+
+/// Side of a link a channel attribute is displayed on.
+/// * [`Primary`][`LinkSide::Primary`]: The side channel loads are displayed on.
+/// * [`Opposite`][`LinkSide::Opposite`]: The other side of the link.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum LinkSide {
+    #[default]
+    Primary,
+    Opposite,
+}
+
+/// A channel attribute to display, and where to display it.
+/// * `key`: The attribute key, which must also be present in [`Configuration`]'s `channel_config`.
+/// * `side`: The [`LinkSide`] to display the attribute on.
+///   Attributes on the same side are stacked along the link, in order.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Debug, Clone)]
+#[getset(get = "pub")]
+pub struct ChannelAttribute {
+    key: String,
+    #[serde(default)]
+    side: LinkSide,
+}
+
+/// Network topology of the rendered system.
+/// * [`Mesh`][`Topology::Mesh`]: Links on the matrix edges connect to border routers (sinks and sources).
+/// * [`Torus`][`Topology::Torus`]: Links on the matrix edges wrap around to the opposite side of the matrix.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Topology {
+    #[default]
+    Mesh,
+    Torus,
+}
+
+/// How the Roboto Mono font is made available to the SVG text.
+/// * [`Full`][`FontEmbedding::Full`]: The whole font is embedded as base64.
+/// * [`Subset`][`FontEmbedding::Subset`]: Only the glyphs used by the SVG text are embedded.
+/// * [`External`][`FontEmbedding::External`]: Nothing is embedded, text uses the font set with [`SVG::set_external_font`].
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum FontEmbedding {
+    #[default]
+    Full,
+    Subset,
+    External,
+}
+
+/// What to do with core and router labels that don't fit inside their element.
+/// * [`Clip`][`LabelOverflow::Clip`]: Labels are cut off by the element's shape.
+/// * [`Shrink`][`LabelOverflow::Shrink`]: Labels are shrunk until they fit, down to the minimum attribute font size.
+/// * [`Ellipsis`][`LabelOverflow::Ellipsis`]: Labels are truncated with an ellipsis, those with no room left are omitted.
+/// * [`Tooltip`][`LabelOverflow::Tooltip`]: Like [`Ellipsis`][`LabelOverflow::Ellipsis`], the full labels are shown on hover.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum LabelOverflow {
+    #[default]
+    Clip,
+    Shrink,
+    Ellipsis,
+    Tooltip,
 }
 
 /// Object representation of user-defined base configuration.
 /// This configuration contains fundamental details of the SVG structure that would require
 /// a full re-generation upon change.
+/// * `side_length`: Side length of a core (and router) shape.
+/// * `router_offset`: How far a router extends above its core.
+/// * `connection_length`: Length of a link between two routers.
+/// * `tooltips`: Whether to add hover tooltips with full details to every element.
+/// * `accessibility`: Whether to add ARIA roles, labels and a textual summary of the system.
+/// * `font_embedding`: How the font is embedded in the SVG.
+/// * `label_overflow`: What to do with core and router labels that don't fit inside their element.
 #[derive(Serialize, Deserialize, Getters, PartialEq, Debug, Clone, Copy)]
 #[getset(get = "pub")]
 pub struct BaseConfiguration {
     attribute_font_size: FontSizeT,
     task_font_size: FontSizeT,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default = "default_side_length")]
+    side_length: CoordinateT,
+    #[serde(default = "default_router_offset")]
+    router_offset: CoordinateT,
+    #[serde(default = "default_connection_length")]
+    connection_length: CoordinateT,
+    #[serde(default)]
+    tooltips: bool,
+    #[serde(default)]
+    accessibility: bool,
+    #[serde(default)]
+    font_embedding: FontEmbedding,
+    #[serde(default)]
+    label_overflow: LabelOverflow,
+}
+
+// Serde defaults, so that base configurations missing geometry keep the original look.
+fn default_side_length() -> CoordinateT {
+    DEFAULT_SIDE_LENGTH
+}
+
+fn default_router_offset() -> CoordinateT {
+    DEFAULT_ROUTER_OFFSET
+}
+
+fn default_connection_length() -> CoordinateT {
+    DEFAULT_CONNECTION_LENGTH
 }
 
 impl BaseConfiguration {
@@ -52,6 +167,14 @@ impl BaseConfiguration {
         Self {
             attribute_font_size,
             task_font_size,
+            topology: Topology::Mesh,
+            side_length: DEFAULT_SIDE_LENGTH,
+            router_offset: DEFAULT_ROUTER_OFFSET,
+            connection_length: DEFAULT_CONNECTION_LENGTH,
+            tooltips: false,
+            accessibility: false,
+            font_embedding: FontEmbedding::Full,
+            label_overflow: LabelOverflow::Clip,
         }
     }
 
@@ -59,6 +182,14 @@ impl BaseConfiguration {
         Self {
             attribute_font_size: DEFAULT_ATTRIBUTE_FONT_SIZE,
             task_font_size: DEFAULT_TASK_FONT_SIZE,
+            topology: Topology::Mesh,
+            side_length: DEFAULT_SIDE_LENGTH,
+            router_offset: DEFAULT_ROUTER_OFFSET,
+            connection_length: DEFAULT_CONNECTION_LENGTH,
+            tooltips: false,
+            accessibility: false,
+            font_embedding: FontEmbedding::Full,
+            label_overflow: LabelOverflow::Clip,
         }
     }
 }
@@ -76,6 +207,80 @@ pub(crate) struct TaskRectConfiguration {
     task_rect_bottom_padding: CoordinateT,
 }
 
+/// This struct contains the system geometry derived from a [`BaseConfiguration`].
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub(crate) struct GeometryConfiguration {
+    side_length: CoordinateT,
+    half_side_length: CoordinateT,
+    router_offset: CoordinateT,
+    half_router_offset: CoordinateT,
+    block_length: CoordinateT,
+    block_distance: CoordinateT,
+    connection_length: CoordinateT,
+    half_connection_length: CoordinateT,
+    three_quarters_connection_length: CoordinateT,
+    connection_gap: CoordinateT,
+    processor_path: String,
+    router_path: String,
+}
+
+impl GeometryConfiguration {
+    /// Generates a new [`GeometryConfiguration`] from the given lengths.
+    /// Lengths are clamped to the bounds advertised in [`CONFIGURABLE_BASE_CONFIGURATION`].
+    /// Router offset is capped at the side length and connection length can't be shorter than
+    /// the router offset, otherwise paths would overlap.
+    fn new(
+        side_length: CoordinateT,
+        router_offset: CoordinateT,
+        connection_length: CoordinateT,
+    ) -> Self {
+        let side_length = side_length.clamp(MINIMUM_SIDE_LENGTH, MAXIMUM_SIDE_LENGTH);
+        let router_offset = router_offset
+            .clamp(MINIMUM_ROUTER_OFFSET, MAXIMUM_ROUTER_OFFSET)
+            .min(side_length);
+        let connection_length = connection_length
+            .clamp(MINIMUM_CONNECTION_LENGTH, MAXIMUM_CONNECTION_LENGTH)
+            .max(router_offset);
+        let half_router_offset = router_offset.saturating_div(2);
+
+        Self {
+            side_length,
+            half_side_length: side_length.saturating_div(2),
+            router_offset,
+            half_router_offset,
+            block_length: side_length.saturating_add(router_offset),
+            block_distance: connection_length
+                .saturating_sub(router_offset)
+                .saturating_add(MARKER_HEIGHT),
+            connection_length,
+            half_connection_length: connection_length
+                .saturating_add(MARKER_HEIGHT)
+                .saturating_div(2),
+            three_quarters_connection_length: connection_length
+                .saturating_add(MARKER_HEIGHT)
+                .saturating_div(4)
+                .saturating_mul(3),
+            connection_gap: half_router_offset
+                .saturating_mul(3)
+                .saturating_div(4)
+                .wrapping_sub(5),
+            // Example with side_length = 100 and router_offset = 75
+            // l0,100 l100,0 l0,-75 l-25,-25 l-75,0 Z
+            processor_path: format!(
+                "l0,{side_length} l{side_length},0 l0,-{router_offset} l-{},-{} l-{router_offset},0 Z",
+                side_length - router_offset,
+                side_length - router_offset,
+            ),
+            // Example with side_length = 100 and router_offset = 75
+            // l0,-75 l100,0 l0,100 l-75,0 Z
+            router_path: format!(
+                "l0,-{router_offset} l{side_length},0 l0,{side_length} l-{router_offset},0 Z"
+            ),
+        }
+    }
+}
+
 /// This struct is used to hold values derived from the user provided [`BaseConfiguration`].
 /// to avoid repeated calculations.
 #[derive(Getters)]
@@ -87,10 +292,20 @@ pub(crate) struct ProcessedBaseConfiguration {
     task_half_font_size_coord: CoordinateT,
     task_rect: TaskRectConfiguration,
     task_rect_with_cost: TaskRectConfiguration,
+    geometry: GeometryConfiguration,
+    sinks_sources_geometry: SinksSourcesGeometry,
+    label_overflow: LabelOverflow,
 }
 
 impl From<&BaseConfiguration> for ProcessedBaseConfiguration {
     fn from(base_configuration: &BaseConfiguration) -> Self {
+        let geometry = GeometryConfiguration::new(
+            base_configuration.side_length,
+            base_configuration.router_offset,
+            base_configuration.connection_length,
+        );
+        let sinks_sources_geometry = SinksSourcesGeometry::from(&geometry);
+
         let task_rect_height =
             (base_configuration.task_font_size.round() as CoordinateT) + CHAR_V_PADDING;
         let task_rect_centre_offset = task_rect_height.div(5);
@@ -119,13 +334,18 @@ impl From<&BaseConfiguration> for ProcessedBaseConfiguration {
                 task_rect_centre_offset: task_rect_with_cost_centre_offset,
                 task_rect_bottom_padding: task_rect_height.sub(task_rect_with_cost_centre_offset),
             },
+            geometry,
+            sinks_sources_geometry,
+            label_overflow: base_configuration.label_overflow,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use manycore_parser::{ManycoreSystem, RoutingAlgorithms, BORDER_ROUTERS_KEY, ROUTING_KEY};
+    use manycore_parser::{
+        Directions, ManycoreSystem, RoutingAlgorithms, BORDER_ROUTERS_KEY, ROUTING_KEY,
+    };
 
     use std::{
         collections::BTreeMap,
@@ -133,9 +353,14 @@ mod tests {
     };
 
     use crate::{
-        tasks_group::MAXIMUM_TASK_FONT_SIZE, BaseConfiguration, ColourSettings, Configuration,
-        CoordinatesOrientation, FieldConfiguration, LoadConfiguration, RoutingConfiguration,
-        MAXIMUM_ATTRIBUTE_FONT_SIZE, SVG,
+        tasks_group::MAXIMUM_TASK_FONT_SIZE,
+        tests::{test_configuration, test_system},
+        BaseConfiguration, ColourSettings, Configuration, CoordinatesOrientation,
+        FieldConfiguration, FontEmbedding, LabelOverflow, LoadConfiguration,
+        ProcessedBaseConfiguration, RoutingConfiguration, Theme, Topology,
+        MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_CONNECTION_LENGTH, MAXIMUM_ROUTER_OFFSET,
+        MAXIMUM_SIDE_LENGTH, MINIMUM_CONNECTION_LENGTH, MINIMUM_ROUTER_OFFSET, MINIMUM_SIDE_LENGTH,
+        SVG,
     };
 
     static BASE_CONFIG: BaseConfiguration = BaseConfiguration::default();
@@ -160,8 +385,9 @@ mod tests {
                 (
                     "@age".to_string(),
                     FieldConfiguration::Fill {
+                        display: None,
                         colour_settings: ColourSettings::new(
-                            [30, 100, 200, 300],
+                            [30.0, 100.0, 200.0, 300.0],
                             [
                                 "#22c55e".to_string(),
                                 "#eab308".to_string(),
@@ -176,7 +402,7 @@ mod tests {
                     FieldConfiguration::ColouredText {
                         display: "Temp".to_string(),
                         colour_settings: ColourSettings::new(
-                            [30, 31, 50, 75],
+                            [30.0, 31.0, 50.0, 75.0],
                             [
                                 "#22c55e".to_string(),
                                 "#eab308".to_string(),
@@ -191,8 +417,9 @@ mod tests {
                 (
                     "@age".to_string(),
                     FieldConfiguration::Fill {
+                        display: None,
                         colour_settings: ColourSettings::new(
-                            [30, 100, 200, 300],
+                            [30.0, 100.0, 200.0, 300.0],
                             [
                                 "#22c55e".to_string(),
                                 "#eab308".to_string(),
@@ -207,7 +434,7 @@ mod tests {
                     FieldConfiguration::ColouredText {
                         display: "Temp".to_string(),
                         colour_settings: ColourSettings::new(
-                            [30, 31, 50, 75],
+                            [30.0, 31.0, 50.0, 75.0],
                             [
                                 "#22c55e".to_string(),
                                 "#eab308".to_string(),
@@ -224,7 +451,7 @@ mod tests {
                     FieldConfiguration::ColouredText {
                         display: "Age".to_string(),
                         colour_settings: ColourSettings::new(
-                            [30, 100, 200, 300],
+                            [30.0, 100.0, 200.0, 300.0],
                             [
                                 "#22c55e".to_string(),
                                 "#eab308".to_string(),
@@ -245,7 +472,7 @@ mod tests {
                             RoutingAlgorithms::RowFirst,
                             LoadConfiguration::Percentage,
                             ColourSettings::new(
-                                [20, 50, 70, 90],
+                                [20.0, 50.0, 70.0, 90.0],
                                 [
                                     "#1a5fb4".to_string(),
                                     "#26a269".to_string(),
@@ -260,6 +487,11 @@ mod tests {
             ]),
             core_fills: BTreeMap::new(),
             router_fills: BTreeMap::new(),
+            theme: Theme::Light,
+            legend: false,
+            task_graph: false,
+            label_placement: false,
+            channel_attributes: Vec::new(),
         };
 
         let conf_file = fs::File::open("tests/conf_test.json")
@@ -276,6 +508,35 @@ mod tests {
         assert_eq!(configuration, expected_configuration)
     }
 
+    #[test]
+    fn can_validate_colour_settings() {
+        let six_bands: ColourSettings = serde_json::from_str(
+            r##"{"bounds": [0, 10, 20, 30, 40, 50], "colours": ["#000000", "#111111", "#222222", "#333333", "#444444", "#555555"]}"##,
+        )
+        .expect("Could not parse six colour bounds");
+        assert_eq!(six_bands.bounds().len(), 6);
+
+        let decimal_bands: ColourSettings = serde_json::from_str(
+            r##"{"bounds": [-12.5, 0, 1e2], "colours": ["#000000", "#111111", "#222222"]}"##,
+        )
+        .expect("Could not parse decimal colour bounds");
+        assert_eq!(decimal_bands.bounds(), &vec![-12.5, 0.0, 100.0]);
+
+        let unsorted = serde_json::from_str::<ColourSettings>(
+            r##"{"bounds": [10, 0], "colours": ["#000000", "#111111"]}"##,
+        );
+        assert!(unsorted.is_err());
+
+        let mismatched = serde_json::from_str::<ColourSettings>(
+            r##"{"bounds": [0, 10, 20], "colours": ["#000000", "#111111"]}"##,
+        );
+        assert!(mismatched.is_err());
+
+        let too_few =
+            serde_json::from_str::<ColourSettings>(r##"{"bounds": [0], "colours": ["#000000"]}"##);
+        assert!(too_few.is_err());
+    }
+
     #[test]
     fn can_generate_according_to_conf() {
         let conf_file =
@@ -346,6 +607,211 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_render_legend() {
+        let mut manycore = test_system();
+        let mut configuration = test_configuration("conf3.json");
+
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+        let view_box: Vec<i32> = update
+            .view_box
+            .split(' ')
+            .map(|value| value.parse().expect("Invalid viewBox value"))
+            .collect();
+
+        configuration.legend = true;
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+        let legend_view_box: Vec<i32> = update
+            .view_box
+            .split(' ')
+            .map(|value| value.parse().expect("Invalid viewBox value"))
+            .collect();
+
+        let legend = &update.information_group[update
+            .information_group
+            .find("<g id=\"legend\">")
+            .expect("Missing legend")..];
+
+        // Five attribute scales and the channel load one, four colours each.
+        assert_eq!(legend.matches("<g><text ").count(), 6);
+        assert_eq!(legend.matches("<rect ").count(), 24);
+        assert!(legend.contains(">Core: age<"));
+        assert!(legend.contains(">Router: Temp<"));
+        assert!(legend.contains(">Channel: Cost (% of bandwidth)<"));
+        assert!(legend.contains(">100 to 200<"));
+        assert!(legend.contains(">≥ 300<"));
+
+        // The legend sits to the right of the system, the viewBox only grows to the right.
+        let legend_x = view_box[0] + view_box[2] + 50;
+        assert!(legend.starts_with(&format!("<g id=\"legend\"><g><text x=\"{legend_x}\"")));
+        assert_eq!(legend_view_box[0..2], view_box[0..2]);
+        assert!(legend_view_box[2] > view_box[2] + 50);
+
+        // Fill scales are named after their display name, when there is one.
+        configuration.core_config.insert(
+            "@age".to_string(),
+            FieldConfiguration::Fill {
+                display: Some("Age".to_string()),
+                colour_settings: ColourSettings::new(
+                    [30.0, 100.0, 200.0, 300.0],
+                    [
+                        "#22c55e".to_string(),
+                        "#eab308".to_string(),
+                        "#f97316".to_string(),
+                        "#dc2626".to_string(),
+                    ],
+                ),
+            },
+        );
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+        assert!(update.information_group.contains(">Core: Age<"));
+        assert!(!update.information_group.contains(">Core: age<"));
+    }
+
+    #[test]
+    fn can_overlay_task_graph() {
+        let mut manycore = test_system();
+        let mut configuration = Configuration {
+            task_graph: true,
+            ..Default::default()
+        };
+
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+
+        let start = update
+            .information_group
+            .find("<g id=\"taskGraph\">")
+            .expect("Missing task graph overlay");
+        let end = start
+            + update.information_group[start..]
+                .find("</text></g></g>")
+                .expect("Unterminated task graph overlay");
+        let overlay = &update.information_group[start..end];
+
+        // Only 2 -> 3 and 3 -> 4 have both tasks allocated, on cores 7 -> 1 and 1 -> 5.
+        assert_eq!(overlay.matches("<g><path ").count(), 2);
+        assert_eq!(overlay.matches("<text ").count(), 2);
+        assert_eq!(overlay.matches("stroke-width=\"0\"").count(), 2);
+
+        // Cheapest edge gets the thinnest line, most expensive one the thickest.
+        let cheapest = overlay.find(">50<").expect("Missing cost label 50");
+        let most_expensive = overlay.find(">100<").expect("Missing cost label 100");
+        assert!(cheapest < most_expensive);
+        assert!(overlay[..cheapest].contains("stroke-width=\"2.0\""));
+        assert!(overlay[cheapest..most_expensive].contains("stroke-width=\"6.0\""));
+
+        configuration.task_graph = false;
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+        assert!(!update.information_group.contains("taskGraph"));
+    }
+
+    #[test]
+    fn can_render_load_heatmap() {
+        let mut manycore = test_system();
+        let mut configuration = test_configuration("conf10.json");
+
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+
+        let link_id = |core_id, direction| {
+            svg.root
+                .connections_group
+                .get_output_connection(&core_id, direction)
+                .expect("Missing output link")
+                .id()
+                .clone()
+        };
+
+        assert!(update.svg.is_none());
+        // Core 4 South carries 0 -> 2 and 3 -> 5, 80 out of 400: 20% of the way to the widest stroke.
+        assert!(update.style.contains(&format!(
+            "\n#{} {{stroke-width: 1.6; stroke: #000;}}",
+            link_id(4, Directions::South)
+        )));
+        // Core 8 routes nothing.
+        assert!(!update
+            .style
+            .contains(&format!("#{} {{", link_id(8, Directions::North))));
+        // Loads are not displayed as text.
+        assert!(!update.information_group.contains("Cost: "));
+    }
+
+    #[test]
+    fn can_stack_channel_attributes() {
+        let mut configuration = test_configuration("conf13.json");
+
+        let mut manycore = test_system();
+
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate update based on configuration.");
+
+        // All three attributes are shown, in the requested order rather than alphabetical one.
+        let status = update
+            .information_group
+            .find("Status: ")
+            .expect("Missing status label");
+        let cost = update
+            .information_group
+            .find("Cost: ")
+            .expect("Missing cost label");
+        let age = update
+            .information_group
+            .find("Age: ")
+            .expect("Missing age label");
+        assert!(status < cost && cost < age);
+    }
+
+    #[test]
+    fn can_fit_labels_in_elements() {
+        let mut configuration = test_configuration("conf3.json");
+
+        let mut manycore = test_system();
+
+        let mut base_configuration =
+            BaseConfiguration::new(MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_TASK_FONT_SIZE);
+        base_configuration.label_overflow = LabelOverflow::Tooltip;
+
+        let mut svg: SVG = (&manycore)
+            .try_into()
+            .expect("Could not convert Manycorer to SVG.");
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &base_configuration)
+            .expect("Could not generate update based on configuration.");
+
+        // Temperatures don't fit a core at the maximum font size.
+        assert!(update.information_group.contains("…"));
+        assert!(update.information_group.contains("<title>"));
+        assert!(update
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.element() == "c0"
+                && diagnostic.attribute() == "@temperature"));
+    }
+
     #[test]
     fn can_flip_coordinates() {
         let conf_file =
@@ -433,6 +899,92 @@ mod tests {
         assert_eq!(res, expected);
     }
 
+    #[test]
+    fn can_render_torus() {
+        let mut configuration = test_configuration("conf3.json");
+
+        let mut manycore = test_system();
+
+        let mut base_configuration =
+            BaseConfiguration::new(MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_TASK_FONT_SIZE);
+        base_configuration.topology = Topology::Torus;
+
+        let mut svg = SVG::try_from_manycore_with_base_config(&manycore, &base_configuration)
+            .expect("Could not convert Manycore to SVG.");
+
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        // No border routers on a torus, every outer link wraps around.
+        assert!(!res.contains("data-link-kind=\"edge\""));
+        assert_eq!(res.matches("data-link-kind=\"wrap\"").count(), 12);
+        // Core 0 North and core 1 North: neighbouring lanes, one lane gap apart.
+        assert!(res.contains("d=\"M-341,-501 V-885 H886 V886 H-341 V441\""));
+        assert!(res.contains("d=\"M73,-501 V-899 H900 V900 H73 V441\""));
+        // Core 2 East takes the innermost lane.
+        assert!(res.contains("d=\"M502,-485 H802 V-801 H-801 V-485 H-440\""));
+        // Core 8 South takes the outermost lane.
+        assert!(res.contains("d=\"M443,427 V956 H-955 V-955 H443 V-515\""));
+        // The viewBox fits the outermost lanes.
+        assert!(res.contains("viewBox=\"-955 -955 1869 1911\""));
+
+        // Core 1 sources on its North link, which wraps around: no source load is rendered.
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &base_configuration)
+            .expect("Could not generate SVG update");
+        assert!(update.diagnostics.iter().any(|diagnostic| {
+            diagnostic.element() == "North channel of c1" && diagnostic.attribute() == "sourceLoad"
+        }));
+
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        #[cfg(feature = "print")]
+        fs::write("tests-out/SVG-torus.svg", res);
+        #[cfg(not(feature = "print"))]
+        assert!(!res.contains("class=\"edgeData"));
+    }
+
+    #[test]
+    fn can_customise_geometry() {
+        let manycore = test_system();
+
+        let mut base_configuration =
+            BaseConfiguration::new(MAXIMUM_ATTRIBUTE_FONT_SIZE, MAXIMUM_TASK_FONT_SIZE);
+        base_configuration.side_length = 150;
+        base_configuration.router_offset = 100;
+        base_configuration.connection_length = 400;
+
+        let svg = SVG::try_from_manycore_with_base_config(&manycore, &base_configuration)
+            .expect("Could not convert Manycore to SVG.");
+        let res = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        // Router 0 and its South link.
+        assert!(res.contains("d=\"M-589,-589 l0,-100 l150,0 l0,150 l-100,0 Z\""));
+        assert!(res.contains("d=\"M-521,-539 v400\""));
+
+        // Out of bounds lengths are clamped.
+        base_configuration.side_length = 0;
+        base_configuration.router_offset = 0;
+        base_configuration.connection_length = 0;
+        let processed = ProcessedBaseConfiguration::from(&base_configuration);
+        assert_eq!(*processed.geometry().side_length(), MINIMUM_SIDE_LENGTH);
+        assert_eq!(*processed.geometry().router_offset(), MINIMUM_ROUTER_OFFSET);
+        assert_eq!(
+            *processed.geometry().connection_length(),
+            MINIMUM_CONNECTION_LENGTH
+        );
+
+        base_configuration.side_length = 10000;
+        base_configuration.router_offset = 10000;
+        base_configuration.connection_length = 10000;
+        let processed = ProcessedBaseConfiguration::from(&base_configuration);
+        assert_eq!(*processed.geometry().side_length(), MAXIMUM_SIDE_LENGTH);
+        assert_eq!(*processed.geometry().router_offset(), MAXIMUM_ROUTER_OFFSET);
+        assert_eq!(
+            *processed.geometry().connection_length(),
+            MAXIMUM_CONNECTION_LENGTH
+        );
+    }
+
     #[test]
     fn can_colour_text() {
         let conf_file: fs::File =
@@ -460,6 +1012,37 @@ mod tests {
         assert_eq!(res, expected);
     }
 
+    #[test]
+    fn can_report_unparseable_values() {
+        let mut configuration = test_configuration("conf9.json");
+
+        let mut manycore = test_system();
+
+        let mut svg: SVG = SVG::try_from(&manycore).expect("Could not convert Manycore to SVG.");
+
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate SVG update");
+
+        // Decimal bounds still colour integer values.
+        assert!(update
+            .information_group
+            .contains("fill=\"#dc2626\">Temp: 45<"));
+        assert!(update
+            .information_group
+            .contains("fill=\"#22c55e\">Temp: 30<"));
+
+        // Every core status is "High". The text is kept, uncoloured.
+        assert_eq!(update.diagnostics().len(), 9);
+        let diagnostic = &update.diagnostics()[0];
+        assert_eq!(diagnostic.element(), "c0");
+        assert_eq!(diagnostic.attribute(), "@status");
+        assert_eq!(diagnostic.value(), "High");
+        assert!(update
+            .information_group
+            .contains("class=\"themeText\">Status: High<"));
+    }
+
     #[test]
     fn can_override_fill() {
         let conf_file: fs::File =
@@ -519,4 +1102,127 @@ mod tests {
             assert_eq!(res, expected);
         }
     }
+
+    #[test]
+    fn can_switch_theme_with_style_only_update() {
+        let mut manycore = test_system();
+
+        let mut svg: SVG = SVG::try_from(&manycore).expect("Could not convert Manycore to SVG.");
+
+        let mut configuration = Configuration::default();
+        configuration.theme = Theme::Dark;
+
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate SVG update");
+
+        assert!(update.svg.is_none());
+        assert!(update.style.contains("--base-fill"));
+        // Themes apply through classes, never by matching generated colours.
+        assert!(update
+            .style
+            .contains(".themeStroke {stroke: var(--stroke);}"));
+        assert!(update.style.contains(".themeText {fill: var(--text);}"));
+        assert!(!update.style.contains("[fill="));
+        assert!(!update.style.contains("[stroke="));
+
+        configuration.theme = Theme::HighContrast;
+        let update = svg
+            .update_configurable_information(&mut manycore, &mut configuration, &BASE_CONFIG)
+            .expect("Could not generate SVG update");
+        assert!(update.style.contains("--stroke: white;"));
+    }
+
+    #[test]
+    fn can_add_tooltips() {
+        let mut manycore = test_system();
+
+        let mut svg: SVG = SVG::try_from(&manycore).expect("Could not convert Manycore to SVG.");
+
+        let mut base_configuration = BaseConfiguration::default();
+        base_configuration.tooltips = true;
+
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut Configuration::default(),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+
+        let res = update
+            .svg
+            .expect("Tooltips should regenerate the whole SVG.");
+        assert!(res.contains("<title>Core 0\nRow 0, column 0"));
+        assert!(res.contains("<desc>"));
+    }
+
+    #[test]
+    fn can_add_accessibility_metadata() {
+        let mut manycore = test_system();
+
+        let mut svg: SVG = SVG::try_from(&manycore).expect("Could not convert Manycore to SVG.");
+
+        let mut base_configuration = BaseConfiguration::default();
+        base_configuration.accessibility = true;
+
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut Configuration::default(),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+
+        let res = update
+            .svg
+            .expect("Accessibility metadata should regenerate the whole SVG.");
+        assert!(res.contains("role=\"img\" aria-labelledby=\"svgTitle svgDesc\""));
+        assert!(res.contains("<desc id=\"svgDesc\">3 by 3 manycore system with 9 cores"));
+        assert!(res.contains("role=\"group\" aria-label=\"Border routers\""));
+    }
+
+    #[test]
+    fn can_embed_font_subset_or_external() {
+        let mut manycore = test_system();
+
+        let mut svg: SVG = SVG::try_from(&manycore).expect("Could not convert Manycore to SVG.");
+        let full_style = svg.style.stylesheet();
+
+        let mut base_configuration = BaseConfiguration::default();
+        base_configuration.font_embedding = FontEmbedding::Subset;
+
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut Configuration::default(),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+
+        assert!(update.style.contains("font-family: \"Roboto Mono\";"));
+        assert!(update.style.len() < full_style.len());
+
+        base_configuration.font_embedding = FontEmbedding::External;
+        svg.set_external_font(
+            String::from("Inter"),
+            Some(String::from("https://example.com/inter.woff2")),
+        )
+        .expect("Could not set external font");
+
+        // The external font outlives the regeneration caused by the new base configuration.
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut Configuration::default(),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+
+        assert!(!update.style.contains("base64"));
+        assert!(update
+            .style
+            .contains("src: url(\"https://example.com/inter.woff2\");"));
+        assert!(update.style.contains("text {font-family: \"Inter\";}"));
+    }
 }
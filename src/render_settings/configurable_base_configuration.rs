@@ -2,8 +2,11 @@ use serde::Serialize;
 
 use crate::{
     tasks_group::DEFAULT_TASK_FONT_SIZE, tasks_group::MAXIMUM_TASK_FONT_SIZE, tasks_group::MINIMUM_TASK_FONT_SIZE,
-    FontSizeT, DEFAULT_ATTRIBUTE_FONT_SIZE, MAXIMUM_ATTRIBUTE_FONT_SIZE,
-    MINIMUM_ATTRIBUTE_FONT_SIZE,
+    CoordinateT, FontSizeT, DEFAULT_ATTRIBUTE_FONT_SIZE, DEFAULT_CONNECTION_LENGTH,
+    DEFAULT_ROUTER_OFFSET, DEFAULT_SIDE_LENGTH, MAXIMUM_ATTRIBUTE_FONT_SIZE,
+    MAXIMUM_CONNECTION_LENGTH, MAXIMUM_ROUTER_OFFSET, MAXIMUM_SIDE_LENGTH,
+    MINIMUM_ATTRIBUTE_FONT_SIZE, MINIMUM_CONNECTION_LENGTH, MINIMUM_ROUTER_OFFSET,
+    MINIMUM_SIDE_LENGTH,
 };
 
 /// Enum whose variants represents specific attribute configuration details for
@@ -17,6 +20,21 @@ pub(crate) enum ConfigurableBaseConfigurationAttributeSpecifics {
         min: FontSizeT,
         max: FontSizeT,
     },
+    Length {
+        default: CoordinateT,
+        display: &'static str,
+        min: CoordinateT,
+        max: CoordinateT,
+    },
+    Options {
+        default: &'static str,
+        display: &'static str,
+        options: &'static [&'static str],
+    },
+    Boolean {
+        default: bool,
+        display: &'static str,
+    },
 }
 
 #[cfg(doc)]
@@ -30,6 +48,14 @@ use crate::BaseConfiguration;
 pub struct ConfigurableBaseConfiguration {
     attribute_font_size: ConfigurableBaseConfigurationAttributeSpecifics,
     task_font_size: ConfigurableBaseConfigurationAttributeSpecifics,
+    topology: ConfigurableBaseConfigurationAttributeSpecifics,
+    side_length: ConfigurableBaseConfigurationAttributeSpecifics,
+    router_offset: ConfigurableBaseConfigurationAttributeSpecifics,
+    connection_length: ConfigurableBaseConfigurationAttributeSpecifics,
+    tooltips: ConfigurableBaseConfigurationAttributeSpecifics,
+    accessibility: ConfigurableBaseConfigurationAttributeSpecifics,
+    font_embedding: ConfigurableBaseConfigurationAttributeSpecifics,
+    label_overflow: ConfigurableBaseConfigurationAttributeSpecifics,
 }
 
 pub static CONFIGURABLE_BASE_CONFIGURATION: ConfigurableBaseConfiguration =
@@ -46,4 +72,45 @@ pub static CONFIGURABLE_BASE_CONFIGURATION: ConfigurableBaseConfiguration =
             min: MINIMUM_TASK_FONT_SIZE,
             max: MAXIMUM_TASK_FONT_SIZE,
         },
+        topology: ConfigurableBaseConfigurationAttributeSpecifics::Options {
+            default: "Mesh",
+            display: "Topology",
+            options: &["Mesh", "Torus"],
+        },
+        side_length: ConfigurableBaseConfigurationAttributeSpecifics::Length {
+            default: DEFAULT_SIDE_LENGTH,
+            display: "Core size",
+            min: MINIMUM_SIDE_LENGTH,
+            max: MAXIMUM_SIDE_LENGTH,
+        },
+        router_offset: ConfigurableBaseConfigurationAttributeSpecifics::Length {
+            default: DEFAULT_ROUTER_OFFSET,
+            display: "Router offset",
+            min: MINIMUM_ROUTER_OFFSET,
+            max: MAXIMUM_ROUTER_OFFSET,
+        },
+        connection_length: ConfigurableBaseConfigurationAttributeSpecifics::Length {
+            default: DEFAULT_CONNECTION_LENGTH,
+            display: "Link length",
+            min: MINIMUM_CONNECTION_LENGTH,
+            max: MAXIMUM_CONNECTION_LENGTH,
+        },
+        tooltips: ConfigurableBaseConfigurationAttributeSpecifics::Boolean {
+            default: false,
+            display: "Hover tooltips",
+        },
+        accessibility: ConfigurableBaseConfigurationAttributeSpecifics::Boolean {
+            default: false,
+            display: "Accessibility metadata",
+        },
+        font_embedding: ConfigurableBaseConfigurationAttributeSpecifics::Options {
+            default: "Full",
+            display: "Font embedding",
+            options: &["Full", "Subset", "External"],
+        },
+        label_overflow: ConfigurableBaseConfigurationAttributeSpecifics::Options {
+            default: "Clip",
+            display: "Label overflow",
+            options: &["Clip", "Shrink", "Ellipsis", "Tooltip"],
+        },
     };
@@ -2,9 +2,36 @@ use getset::Getters;
 use manycore_parser::RoutingAlgorithms;
 use serde::{Deserialize, Serialize};
 
+use crate::{SVGError, SVGErrorKind};
+
+/// How colours are picked between [`ColourSettings`] bounds.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, PartialOrd, Eq, Ord, Default)]
+pub enum ColourInterpolation {
+    /// Each value takes the colour of the bound at or below it. This is the default.
+    #[default]
+    Discrete,
+    /// Bounds are gradient stops, colours are interpolated in linear-light RGB.
+    LinearRgb,
+    /// Bounds are gradient stops, colours are interpolated in the OKLab perceptual colour space.
+    Oklab,
+}
+
+/// Minimum number of bounds (and colours) in a [`ColourSettings`] instance.
+pub const MINIMUM_COLOUR_BOUNDS: usize = 2;
+/// Maximum number of bounds (and colours) in a [`ColourSettings`] instance.
+pub const MAXIMUM_COLOUR_BOUNDS: usize = 16;
+
 /// Configuration colour settings
-/// * `bounds`: Numerical boundaries. Used to determine colour.
+/// * `bounds`: Numerical boundaries, sorted in ascending order. Used to determine colour. Can be decimal or negative.
 /// * `colours`: List of colours (hexadecimal) corresponding to each boundary.
+/// * `interpolation`: [`ColourInterpolation`] mode, discrete if missing.
+///
+/// There can be between [`MINIMUM_COLOUR_BOUNDS`] and [`MAXIMUM_COLOUR_BOUNDS`] bounds,
+/// with exactly one colour per bound. Both are validated on deserialisation.
+///
+/// **Breaking change:** bounds used to be four unsigned integers, they are now a [`Vec`] of [`f64`]
+/// so that decimal and negative values can be coloured. As a consequence, [`ColourSettings`] no longer
+/// implements [`Eq`] and [`Ord`].
 ///
 /// Example, given:
 /// ```ignore
@@ -23,25 +50,92 @@ use serde::{Deserialize, Serialize};
 /// | `11`            | `#eab308` |
 /// | `35`            | `#f97316` |
 /// | `50`            | `#dc2626` |
-#[derive(Serialize, Deserialize, Getters, PartialEq, Debug, PartialOrd, Eq, Ord)]
+///
+/// With a continuous [`ColourInterpolation`], `15` would instead get the colour halfway between
+/// `#22c55e` and `#eab308`. Values outside the bounds take the closest bound colour.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Debug, Clone, PartialOrd)]
+#[serde(try_from = "UncheckedColourSettings")]
 #[getset(get = "pub")]
 pub struct ColourSettings {
-    bounds: [u64; 4],
-    colours: [String; 4],
+    bounds: Vec<f64>,
+    colours: Vec<String>,
+    interpolation: ColourInterpolation,
+}
+
+/// [`ColourSettings`] as found in the configuration, before validation.
+#[derive(Deserialize)]
+struct UncheckedColourSettings {
+    bounds: Vec<f64>,
+    colours: Vec<String>,
+    #[serde(default)]
+    interpolation: ColourInterpolation,
+}
+
+impl TryFrom<UncheckedColourSettings> for ColourSettings {
+    type Error = SVGError;
+
+    fn try_from(unchecked: UncheckedColourSettings) -> Result<Self, Self::Error> {
+        let UncheckedColourSettings {
+            bounds,
+            colours,
+            interpolation,
+        } = unchecked;
+
+        if !(MINIMUM_COLOUR_BOUNDS..=MAXIMUM_COLOUR_BOUNDS).contains(&bounds.len()) {
+            return Err(SVGError::new(SVGErrorKind::DataConversionError(format!(
+                "Colour settings must have between {} and {} bounds, found {}.",
+                MINIMUM_COLOUR_BOUNDS,
+                MAXIMUM_COLOUR_BOUNDS,
+                bounds.len()
+            ))));
+        }
+
+        if bounds.len() != colours.len() {
+            return Err(SVGError::new(SVGErrorKind::DataConversionError(format!(
+                "Colour settings must have one colour per bound, found {} bounds and {} colours.",
+                bounds.len(),
+                colours.len()
+            ))));
+        }
+
+        if bounds.iter().any(|bound| !bound.is_finite()) {
+            return Err(SVGError::new(SVGErrorKind::DataConversionError(format!(
+                "Colour settings bounds must be finite numbers, found {:?}.",
+                bounds
+            ))));
+        }
+
+        if bounds.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(SVGError::new(SVGErrorKind::DataConversionError(format!(
+                "Colour settings bounds must be sorted in ascending order, found {:?}.",
+                bounds
+            ))));
+        }
+
+        Ok(Self {
+            bounds,
+            colours,
+            interpolation,
+        })
+    }
 }
 
 impl ColourSettings {
     #[cfg(test)]
     /// Generates a new [`ColourSettings`] from the given parameters.
-    pub(crate) fn new(bounds: [u64; 4], colours: [String; 4]) -> Self {
-        Self { bounds, colours }
+    pub(crate) fn new<const N: usize>(bounds: [f64; N], colours: [String; N]) -> Self {
+        Self {
+            bounds: bounds.into(),
+            colours: colours.into(),
+            interpolation: ColourInterpolation::Discrete,
+        }
     }
 }
 
 /// Configuration coordinates orientation settins.
 /// * [`T`][`CoordinatesOrientation::T`]: Top to bottom
 /// * [`B`][`CoordinatesOrientation::B`]: Bottom to top
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub enum CoordinatesOrientation {
     T,
     B,
@@ -52,7 +146,7 @@ pub enum CoordinatesOrientation {
 /// * `load_configuration`: [`LoadConfiguration`]
 /// * `load_colours`: [`ColourSettings`]
 /// * `display`: [`String`], the display key of channel loads.
-#[derive(Serialize, Deserialize, Getters, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Getters, PartialEq, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[getset(get = "pub")]
 pub struct RoutingConfiguration {
@@ -81,16 +175,18 @@ impl RoutingConfiguration {
 }
 
 /// Channel load configuration.
-#[derive(Serialize, Deserialize, PartialEq, Debug, PartialOrd, Eq, Ord)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, PartialOrd, Eq, Ord)]
 pub enum LoadConfiguration {
     /// Display loads as percentage of bandwidth, e.g. 5%.
     Percentage,
     /// Display loads as fraction of bandwith, e.g. 20/400.
     Fraction,
+    /// Colour and thicken each link according to its load percentage, without text.
+    Heatmap,
 }
 
 /// Possible ways a field can be configured.
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum FieldConfiguration {
     /// Text only.
@@ -105,7 +201,10 @@ pub enum FieldConfiguration {
         colour_settings: ColourSettings,
     },
     /// Fill colour of associated element, according to provided [`ColourSettings`].
+    /// `display` names the field in the legend, the attribute name is used if missing.
     Fill {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
         #[serde(rename = "colourSettings", flatten)]
         colour_settings: ColourSettings,
     },
@@ -0,0 +1,479 @@
+use std::{cell::Cell, fmt, io};
+
+use flate2::{write::GzEncoder, Compression};
+use getset::{Getters, Setters};
+use quick_xml::{
+    se::{QuoteLevel, Serializer},
+    DeError,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{partial_update::PartialUpdate, SVGError, SVGErrorKind, SVG};
+
+static XML_DECLARATION: &'static str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+
+thread_local! {
+    /// Precision of the [`SerialiseOptions`] currently writing, if any.
+    static PRECISION: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+/// Restores the previous precision once a write ends, even if it failed.
+struct PrecisionGuard(Option<u8>);
+
+impl Drop for PrecisionGuard {
+    fn drop(&mut self) {
+        PRECISION.with(|precision| precision.set(self.0));
+    }
+}
+
+/// Formats a fractional attribute value, e.g. a font size, with the precision of the [`SerialiseOptions`]
+/// currently writing. Trailing zeros are dropped. Returns [`None`] if no precision was requested.
+pub(crate) fn precise_decimal(value: f64) -> Option<String> {
+    let precision = PRECISION.with(Cell::get)?;
+    let mut ret = format!("{:.*}", usize::from(precision), value);
+
+    if ret.contains('.') {
+        let significant = ret.trim_end_matches('0').trim_end_matches('.').len();
+        ret.truncate(significant);
+    }
+
+    Some(ret)
+}
+
+/// How serialised elements are laid out.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Indentation {
+    /// Everything on a single line.
+    Compact,
+    /// One element per line, nested elements indented by `size` repetitions of `character`.
+    Indent { character: char, size: usize },
+}
+
+/// How attribute values are quoted and escaped.
+/// Mirrors quick-xml's quote levels, see <https://docs.rs/quick-xml/latest/quick_xml/se/enum.QuoteLevel.html>.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum AttributeQuoting {
+    /// Escapes only what is required for the output to be valid XML.
+    Minimal,
+    /// Also escapes `>`.
+    Partial,
+    /// Escapes every XML special character.
+    Full,
+}
+
+impl From<AttributeQuoting> for QuoteLevel {
+    fn from(quoting: AttributeQuoting) -> Self {
+        match quoting {
+            AttributeQuoting::Minimal => QuoteLevel::Minimal,
+            AttributeQuoting::Partial => QuoteLevel::Partial,
+            AttributeQuoting::Full => QuoteLevel::Full,
+        }
+    }
+}
+
+/// Object representation of the options used when serialising an [`SVG`], or part of it.
+/// * `indentation`: Compact or indented output.
+/// * `quoting`: How attribute values are escaped.
+/// * `xml_declaration`: Whether to prepend an XML declaration. Only applies to whole documents.
+/// * `gzip`: Whether to compress the output as SVGZ. Only applies to byte outputs: the options of string outputs,
+///   i.e. [`crate::UpdateResult`]s and flow highlights, can't request it.
+/// * `precision`: Maximum number of decimals of fractional attributes, i.e. font sizes. These are written
+///   in whole pixels if not provided.
+#[derive(Serialize, Deserialize, Getters, Setters, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[getset(get = "pub", set = "pub")]
+pub struct SerialiseOptions {
+    indentation: Indentation,
+    quoting: AttributeQuoting,
+    #[serde(default)]
+    xml_declaration: bool,
+    #[serde(default)]
+    gzip: bool,
+    #[serde(default)]
+    precision: Option<u8>,
+}
+
+impl Default for SerialiseOptions {
+    /// Pretty printed output, as written to `.svg` files.
+    fn default() -> Self {
+        Self {
+            indentation: Indentation::Indent {
+                character: ' ',
+                size: 4,
+            },
+            quoting: AttributeQuoting::Minimal,
+            xml_declaration: false,
+            gzip: false,
+            precision: None,
+        }
+    }
+}
+
+impl SerialiseOptions {
+    /// Single line output, as sent to the front-end in an [`crate::UpdateResult`].
+    /// Matches the defaults of quick-xml's serialiser, so updates are written as they always were.
+    pub const fn compact() -> Self {
+        Self {
+            indentation: Indentation::Compact,
+            quoting: AttributeQuoting::Full,
+            xml_declaration: false,
+            gzip: false,
+            precision: None,
+        }
+    }
+
+    /// Writes `value` into `writer` according to these options.
+    /// `root` is the element name to use, if `value`'s type does not provide one (e.g. sequences).
+    pub(crate) fn write_fragment<T: Serialize, W: fmt::Write>(
+        &self,
+        value: &T,
+        root: Option<&str>,
+        mut writer: W,
+    ) -> Result<(), DeError> {
+        let mut serialiser = Serializer::with_root(&mut writer, root)?;
+        if let Indentation::Indent { character, size } = self.indentation {
+            serialiser.indent(character, size);
+        }
+        serialiser.set_quote_level(self.quoting.into());
+
+        let _precision =
+            PrecisionGuard(PRECISION.with(|precision| precision.replace(self.precision)));
+        value.serialize(serialiser)?;
+
+        Ok(())
+    }
+
+    /// Writes a whole document into `writer` according to these options, including the XML declaration if requested.
+    pub(crate) fn write_document<T: Serialize, W: fmt::Write>(
+        &self,
+        value: &T,
+        mut writer: W,
+    ) -> Result<(), DeError> {
+        if self.xml_declaration {
+            writer.write_str(XML_DECLARATION)?;
+            if let Indentation::Indent { .. } = self.indentation {
+                writer.write_char('\n')?;
+            }
+        }
+
+        self.write_fragment(value, None, writer)
+    }
+
+    /// Serialises a whole document according to these options, including the XML declaration if requested.
+    pub(crate) fn serialise_document<T: Serialize>(&self, value: &T) -> Result<String, DeError> {
+        let mut buf = String::new();
+        self.write_document(value, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Runs `write` against `writer`, compressing the output if requested.
+    fn stream<W, F>(&self, writer: W, write: F) -> Result<(), SVGError>
+    where
+        W: io::Write,
+        F: FnOnce(&mut dyn fmt::Write) -> Result<(), DeError>,
+    {
+        if self.gzip {
+            let mut adapter = IoAdapter::new(GzEncoder::new(writer, Compression::default()));
+            adapter.run(write)?;
+            adapter.writer.finish().map_err(io_error)?;
+        } else {
+            let mut adapter = IoAdapter::new(writer);
+            adapter.run(write)?;
+            adapter.writer.flush().map_err(io_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Utility to wrap an [`io::Error`] raised while writing the output.
+fn io_error(error: io::Error) -> SVGError {
+    SVGError::new(SVGErrorKind::SerialisationError(error.to_string()))
+}
+
+/// Adapter that lets quick-xml, which writes into a [`fmt::Write`], stream into an [`io::Write`].
+struct IoAdapter<W: io::Write> {
+    writer: W,
+    /// The last [`io::Error`], as [`fmt::Error`] can't carry it.
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoAdapter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Runs `write` against the adapter. If writing failed, the underlying [`io::Error`] is returned
+    /// instead of quick-xml's generic formatting error.
+    fn run<F>(&mut self, write: F) -> Result<(), SVGError>
+    where
+        F: FnOnce(&mut dyn fmt::Write) -> Result<(), DeError>,
+    {
+        let result = write(self);
+
+        match self.error.take() {
+            Some(error) => Err(io_error(error)),
+            None => Ok(result?),
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+impl SVG {
+    /// Sets the [`SerialiseOptions`] used for [`crate::UpdateResult`]s and flow highlights.
+    /// These are strings, so gzip compression is rejected: use [`SVG::serialise`] or the `write_*` methods for SVGZ.
+    pub fn set_update_serialise_options(
+        &mut self,
+        options: SerialiseOptions,
+    ) -> Result<(), SVGError> {
+        if options.gzip {
+            return Err(SVGError::new(SVGErrorKind::SerialisationError(
+                String::from("Updates are strings, they can't be gzip compressed."),
+            )));
+        }
+
+        self.update_serialise_options = options;
+
+        Ok(())
+    }
+
+    /// Streams the [`SVG`] into `writer` according to the provided [`SerialiseOptions`].
+    /// Nothing is buffered, so large systems can be written straight to a file or socket.
+    /// The output is produced in many small writes: wrap files and sockets in an [`io::BufWriter`].
+    pub fn write_to<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), SVGError> {
+        options.stream(writer, |writer| options.write_document(self, writer))
+    }
+
+    /// Serialises the [`SVG`] according to the provided [`SerialiseOptions`].
+    /// The result is UTF-8 text, unless gzip compression was requested.
+    pub fn serialise(&self, options: &SerialiseOptions) -> Result<Vec<u8>, SVGError> {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf, options)?;
+
+        Ok(buf)
+    }
+
+    /// Streams the information group update fragment, as found in an [`crate::UpdateResult`], into `writer`.
+    /// As with [`SVG::write_to`], unbuffered writers should be wrapped in an [`io::BufWriter`].
+    pub fn write_information_group<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), SVGError> {
+        options.stream(writer, |writer| {
+            self.root.information_group.write_update(writer, options)
+        })
+    }
+
+    /// Streams the tasks group update fragment, as found in an [`crate::UpdateResult`], into `writer`.
+    pub fn write_tasks_group<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), SVGError> {
+        options.stream(writer, |writer| {
+            self.root.tasks_group.write_update(writer, options)
+        })
+    }
+
+    /// Streams the current flow highlight fragment, as returned by [`SVG::highlight_flow`], into `writer`.
+    /// Nothing is written if no flow is highlighted.
+    pub fn write_flow_highlight<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), SVGError> {
+        match &self.root.flow_highlight {
+            Some(flow_highlight) => options.stream(writer, |writer| {
+                flow_highlight.write_update(writer, options)
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use flate2::read::GzDecoder;
+
+    use crate::{
+        tasks_group::Task,
+        tests::{test_configuration, test_system},
+        BaseConfiguration, SVGError, SVG,
+    };
+
+    use super::SerialiseOptions;
+
+    /// Writer that fails every write, e.g. a full disk.
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn test_svg() -> SVG {
+        (&test_system())
+            .try_into()
+            .expect("Could not convert Manycore to SVG.")
+    }
+
+    #[test]
+    fn can_write_to() {
+        let svg = test_svg();
+        let expected = String::try_from(&svg).expect("Could not convert from SVG to string");
+
+        let mut written = Vec::new();
+        svg.write_to(&mut written, &SerialiseOptions::default())
+            .expect("Could not stream SVG.");
+        assert_eq!(
+            String::from_utf8(written).expect("Streamed SVG is not UTF-8."),
+            expected
+        );
+
+        // The compressed stream is complete, i.e. the encoder was finished.
+        let mut options = SerialiseOptions::default();
+        options.set_gzip(true);
+        let mut compressed = Vec::new();
+        svg.write_to(&mut compressed, &options)
+            .expect("Could not stream SVGZ.");
+
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decompressed)
+            .expect("Could not decompress SVGZ.");
+        assert_eq!(decompressed, expected);
+    }
+
+    #[test]
+    fn can_write_groups() {
+        let mut manycore = test_system();
+        let mut configuration = test_configuration("conf3.json");
+        let mut svg = test_svg();
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut configuration,
+                &BaseConfiguration::default(),
+            )
+            .expect("Could not generate update based on configuration.");
+
+        // Streams match the fragments of an update.
+        let stream = |write: &dyn Fn(&mut Vec<u8>) -> Result<(), SVGError>| {
+            let mut buf = Vec::new();
+            write(&mut buf).expect("Could not stream group.");
+
+            String::from_utf8(buf).expect("Streamed group is not UTF-8.")
+        };
+        assert_eq!(
+            stream(&|buf| svg.write_information_group(buf, &SerialiseOptions::compact())),
+            update.information_group
+        );
+        assert_eq!(
+            stream(&|buf| svg.write_tasks_group(buf, &SerialiseOptions::compact())),
+            update.tasks_group
+        );
+        // Nothing is highlighted.
+        assert!(
+            stream(&|buf| svg.write_flow_highlight(buf, &SerialiseOptions::compact())).is_empty()
+        );
+    }
+
+    #[test]
+    fn passes_write_errors_through() {
+        let svg = test_svg();
+        let mut options = SerialiseOptions::default();
+
+        // The underlying error is reported, not quick-xml's generic formatting error.
+        for gzip in [false, true] {
+            options.set_gzip(gzip);
+            let error = svg
+                .write_to(FailingWriter, &options)
+                .err()
+                .expect("Writing to a failing writer succeeded.");
+            assert_eq!(error.to_string(), "Serialisation Error: disk full");
+        }
+
+        let error = svg
+            .write_tasks_group(FailingWriter, &SerialiseOptions::compact())
+            .err()
+            .expect("Writing to a failing writer succeeded.");
+        assert_eq!(error.to_string(), "Serialisation Error: disk full");
+    }
+
+    #[test]
+    fn rejects_gzip_updates() {
+        let mut svg = test_svg();
+        let mut options = SerialiseOptions::default();
+        options.set_gzip(true);
+
+        let error = svg
+            .set_update_serialise_options(options)
+            .err()
+            .expect("Gzip compressed updates were accepted.");
+        assert_eq!(
+            error.to_string(),
+            "Serialisation Error: Updates are strings, they can't be gzip compressed."
+        );
+        assert_eq!(*svg.update_serialise_options(), SerialiseOptions::compact());
+
+        options.set_gzip(false);
+        svg.set_update_serialise_options(options)
+            .expect("Could not set update options.");
+        assert_eq!(*svg.update_serialise_options(), options);
+    }
+
+    #[test]
+    fn keeps_default_update_format() {
+        let mut manycore = test_system();
+        let mut svg = test_svg();
+
+        // A new base configuration sends the whole SVG too.
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut test_configuration("conf3.json"),
+                &BaseConfiguration::new(18.0, 22.0),
+            )
+            .expect("Could not generate update based on configuration.");
+
+        // Updates are written as quick-xml's default serialiser writes them.
+        assert_eq!(
+            update.svg,
+            Some(quick_xml::se::to_string(&svg).expect("Could not serialise SVG."))
+        );
+        let tasks: Vec<&Task> = svg.root.tasks_group.into_iter().collect();
+        assert_eq!(
+            update.tasks_group,
+            quick_xml::se::to_string_with_root("g", &tasks).expect("Could not serialise tasks.")
+        );
+        assert_eq!(
+            update.information_group,
+            quick_xml::se::to_string_with_root("g", &*svg.root.information_group.groups_mut())
+                .expect("Could not serialise information.")
+        );
+    }
+}
@@ -1,12 +1,14 @@
 use std::{cmp::max, collections::HashMap};
 
 use const_format::concatcp;
-use getset::Getters;
-use manycore_parser::{BorderEntry, EdgePosition, SinkSourceDirection, SystemDimensionsT};
+use getset::{Getters, Setters};
+use manycore_parser::{
+    BorderEntry, EdgePosition, ElementIDT, SinkSourceDirection, SystemDimensionsT,
+};
 use serde::Serialize;
 
 use crate::{
-    style::{DEFAULT_FILL, EDGE_DATA_CLASS_NAME}, CoordinateT, Offsets, ProcessedBaseConfiguration, TextInformation, CHAR_H_PADDING, HALF_ROUTER_OFFSET, MARKER_HEIGHT, ROUTER_OFFSET, SIDE_LENGTH, UNSUPPORTED_PLATFORM, USE_FREEFORM_CLIP_PATH
+    style::{DEFAULT_FILL, EDGE_DATA_CLASS_NAME}, CoordinateT, DataAttributes, GeometryConfiguration, GroupLabel, Offsets, ProcessedBaseConfiguration, TextInformation, BASE_FILL_STROKE_CLASS_NAME, CHAR_H_PADDING, LIGHT_COLOURS, MARKER_HEIGHT, SINK_CLASS_NAME, SOURCE_CLASS_NAME, STROKE_CLASS_NAME, UNSUPPORTED_PLATFORM, USE_FREEFORM_CLIP_PATH
 };
 
 // Side lengths
@@ -21,25 +23,80 @@ pub(crate) const SINKS_SOURCES_STROKE_WIDTH: CoordinateT = 1;
 static SINKS_SOURCES_STROKE_WIDTH_STR: &'static str = concatcp!(SINKS_SOURCES_STROKE_WIDTH);
 static SINKS_SOURCES_RX: &str = "15";
 
-// Connection
-pub(crate) static SINKS_SOURCES_CONNECTION_LENGTH: CoordinateT = ROUTER_OFFSET.saturating_mul(3);
-pub(crate) static NORTH_SINKS_SOURCES_CONNECTION_DELTA: CoordinateT =
-    SINKS_SOURCES_CONNECTION_LENGTH
-        .saturating_add(MARKER_HEIGHT)
-        .saturating_div(2);
-pub(crate) static SOUTH_SINKS_SOURCES_CONNECTION_DELTA: CoordinateT =
-    NORTH_SINKS_SOURCES_CONNECTION_DELTA.saturating_add(HALF_ROUTER_OFFSET);
-pub(crate) static EAST_SINKS_SOURCES_CONNECTION_DELTA: CoordinateT =
-    SINKS_SOURCES_CONNECTION_LENGTH
-        .saturating_add(MARKER_HEIGHT)
-        .saturating_div(4)
-        .saturating_mul(3);
-pub(crate) static WEST_SINKS_SOURCES_CONNECTION_DELTA: CoordinateT =
-    EAST_SINKS_SOURCES_CONNECTION_DELTA
-        .saturating_add(ROUTER_OFFSET.saturating_div(4).saturating_mul(3));
-
-static SINK_FILL: &str = "#fb923c";
-static SOURCE_FILL: &str = "#fbbf24";
+/// This struct contains sinks and sources geometry, derived from a [`GeometryConfiguration`].
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub(crate) struct SinksSourcesGeometry {
+    connection_length: CoordinateT,
+    north_connection_delta: CoordinateT,
+    south_connection_delta: CoordinateT,
+    east_connection_delta: CoordinateT,
+    west_connection_delta: CoordinateT,
+    north_delta_y: CoordinateT,
+    south_delta_y: CoordinateT,
+    north_south_delta_x: CoordinateT,
+    east_west_delta_y: CoordinateT,
+    east_delta_x: CoordinateT,
+    west_delta_x: CoordinateT,
+}
+
+impl From<&GeometryConfiguration> for SinksSourcesGeometry {
+    fn from(geometry: &GeometryConfiguration) -> Self {
+        let side_length = *geometry.side_length();
+        let router_offset = *geometry.router_offset();
+        let half_router_offset = *geometry.half_router_offset();
+        let connection_length = geometry
+            .connection_length()
+            .saturating_div(4)
+            .saturating_mul(3);
+
+        let north_connection_delta = connection_length
+            .saturating_add(MARKER_HEIGHT)
+            .saturating_div(2);
+        let east_connection_delta = connection_length
+            .saturating_add(MARKER_HEIGHT)
+            .saturating_div(4)
+            .saturating_mul(3);
+
+        Self {
+            connection_length,
+            north_connection_delta,
+            south_connection_delta: north_connection_delta.saturating_add(half_router_offset),
+            east_connection_delta,
+            west_connection_delta: east_connection_delta
+                .saturating_add(router_offset.saturating_div(4).saturating_mul(3)),
+            north_delta_y: 0i32
+                .saturating_sub(connection_length)
+                .saturating_sub(SINKS_SOURCES_HALF_SHORT_SIDE_LENGTH)
+                .saturating_sub(router_offset)
+                .saturating_sub(MARKER_HEIGHT),
+            south_delta_y: 0i32
+                .saturating_add(side_length)
+                .saturating_add(connection_length)
+                .saturating_add(MARKER_HEIGHT)
+                .saturating_add(SINKS_SOURCES_HALF_SHORT_SIDE_LENGTH),
+            north_south_delta_x: 0i32
+                .saturating_add(side_length)
+                .saturating_sub(half_router_offset),
+            east_west_delta_y: 0i32
+                .saturating_sub(router_offset)
+                .saturating_add(half_router_offset),
+            east_delta_x: 0i32
+                .saturating_add(connection_length)
+                .saturating_add(side_length)
+                .saturating_add(MARKER_HEIGHT),
+            west_delta_x: 0i32
+                .saturating_sub(connection_length)
+                .saturating_sub(MARKER_HEIGHT)
+                .saturating_sub(router_offset),
+        }
+    }
+}
+
+static SINK_FILL: &str = LIGHT_COLOURS.sink_fill;
+static SOURCE_FILL: &str = LIGHT_COLOURS.source_fill;
+static SINK_RECT_CLASS_NAME: &str = concatcp!(SINK_CLASS_NAME, " ", STROKE_CLASS_NAME);
+static SOURCE_RECT_CLASS_NAME: &str = concatcp!(SOURCE_CLASS_NAME, " ", STROKE_CLASS_NAME);
 
 pub const SINK_SOURCES_ID: &'static str = "sinksSources";
 
@@ -82,6 +139,8 @@ pub(crate) struct Rect {
     height: &'static str,
     #[serde(rename = "@rx")]
     rx: &'static str,
+    #[serde(rename = "@class")]
+    class: &'static str,
     #[serde(rename = "@fill")]
     fill: &'static str,
     #[serde(rename = "@stroke")]
@@ -104,47 +163,22 @@ impl Rect {
             width: text_width,
             height: SINKS_SOURCES_SHORT_SIDE_LENGTH_STR,
             rx: SINKS_SOURCES_RX,
+            class: match variant {
+                SinkSourceVariant::Source(_) => SOURCE_RECT_CLASS_NAME,
+                SinkSourceVariant::Sink(_) => SINK_RECT_CLASS_NAME,
+                SinkSourceVariant::None => BASE_FILL_STROKE_CLASS_NAME,
+            },
             fill: match variant {
                 SinkSourceVariant::Source(_) => SOURCE_FILL,
                 SinkSourceVariant::Sink(_) => SINK_FILL,
                 SinkSourceVariant::None => DEFAULT_FILL,
             },
-            stroke: "black",
+            stroke: LIGHT_COLOURS.stroke,
             stroke_width: SINKS_SOURCES_STROKE_WIDTH_STR,
         }
     }
 }
 
-static NORTH_DELTA_Y: CoordinateT = 0i32
-    .saturating_sub(SINKS_SOURCES_CONNECTION_LENGTH)
-    .saturating_sub(SINKS_SOURCES_HALF_SHORT_SIDE_LENGTH)
-    .saturating_sub(ROUTER_OFFSET)
-    .saturating_sub(MARKER_HEIGHT);
-
-static SOUTH_DELTA_Y: CoordinateT = 0i32
-    .saturating_add(SIDE_LENGTH)
-    .saturating_add(SINKS_SOURCES_CONNECTION_LENGTH)
-    .saturating_add(MARKER_HEIGHT)
-    .saturating_add(SINKS_SOURCES_HALF_SHORT_SIDE_LENGTH);
-
-static NORTH_SOUTH_DELTA_X: CoordinateT = 0i32
-    .saturating_add(SIDE_LENGTH)
-    .saturating_sub(HALF_ROUTER_OFFSET);
-
-static EAST_WEST_DELTA_Y: CoordinateT = 0i32
-    .saturating_sub(ROUTER_OFFSET)
-    .saturating_add(HALF_ROUTER_OFFSET);
-
-static EAST_DELTA_X: CoordinateT = 0i32
-    .saturating_add(SINKS_SOURCES_CONNECTION_LENGTH)
-    .saturating_add(SIDE_LENGTH)
-    .saturating_add(MARKER_HEIGHT);
-
-static WEST_DELTA_X: CoordinateT = 0i32
-    .saturating_sub(SINKS_SOURCES_CONNECTION_LENGTH)
-    .saturating_sub(MARKER_HEIGHT)
-    .saturating_sub(ROUTER_OFFSET);
-
 /// Enum to describe an router variant. Variant content is task id, if any.
 #[derive(Clone, Copy)]
 pub(crate) enum SinkSourceVariant {
@@ -165,6 +199,8 @@ impl From<&BorderEntry> for SinkSourceVariant {
 /// Object representation of an SVG border router `<g>`.
 #[derive(Serialize, Getters)]
 pub(crate) struct SinkSource {
+    #[serde(flatten)]
+    data: DataAttributes,
     #[getset(get = "pub")]
     rect: Rect,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -174,6 +210,7 @@ pub(crate) struct SinkSource {
 impl SinkSource {
     /// Generates a new [`SinkSource`] instance according to the provided parameters.
     pub(crate) fn new(
+        data: DataAttributes,
         router_x: &CoordinateT,
         router_y: &CoordinateT,
         direction: &SinkSourceDirection,
@@ -182,6 +219,7 @@ impl SinkSource {
     ) -> Self {
         let delta_x;
         let delta_y;
+        let geometry = processed_base_configuration.sinks_sources_geometry();
 
         let text_content = match variant {
             SinkSourceVariant::Sink(task_id) => Some(format!("T{}", task_id)),
@@ -190,14 +228,12 @@ impl SinkSource {
         };
 
         let text_width = match text_content.as_ref() {
-            // TODO: This should bubble up error
             Some(text_content) => max(
                 TextInformation::calculate_length_util(
                     *processed_base_configuration.task_font_size(),
-                    text_content.len(),
+                    text_content,
                     Some(CHAR_H_PADDING),
-                )
-                .unwrap_or(SINKS_SOURCES_SHORT_SIDE_LENGTH),
+                ),
                 SINKS_SOURCES_SHORT_SIDE_LENGTH,
             ),
             None => SINKS_SOURCES_SHORT_SIDE_LENGTH,
@@ -205,20 +241,24 @@ impl SinkSource {
 
         match direction {
             SinkSourceDirection::North => {
-                delta_y = NORTH_DELTA_Y;
-                delta_x = NORTH_SOUTH_DELTA_X;
+                delta_y = *geometry.north_delta_y();
+                delta_x = *geometry.north_south_delta_x();
             }
             SinkSourceDirection::East => {
-                delta_y = EAST_WEST_DELTA_Y;
-                delta_x = EAST_DELTA_X.saturating_add(text_width.saturating_div(2));
+                delta_y = *geometry.east_west_delta_y();
+                delta_x = geometry
+                    .east_delta_x()
+                    .saturating_add(text_width.saturating_div(2));
             }
             SinkSourceDirection::South => {
-                delta_y = SOUTH_DELTA_Y;
-                delta_x = NORTH_SOUTH_DELTA_X;
+                delta_y = *geometry.south_delta_y();
+                delta_x = *geometry.north_south_delta_x();
             }
             SinkSourceDirection::West => {
-                delta_y = EAST_WEST_DELTA_Y;
-                delta_x = WEST_DELTA_X.saturating_sub(text_width.saturating_div(2));
+                delta_y = *geometry.east_west_delta_y();
+                delta_x = geometry
+                    .west_delta_x()
+                    .saturating_sub(text_width.saturating_div(2));
             }
         };
 
@@ -226,6 +266,7 @@ impl SinkSource {
         let centre_y = delta_y.wrapping_add(*router_y);
 
         SinkSource {
+            data,
             rect: Rect::new(centre_x, centre_y, variant, text_width),
             text: TextInformation::sink_source_text(
                 centre_x,
@@ -238,7 +279,7 @@ impl SinkSource {
 }
 
 /// Object representation of an SVG `<g>` that contains all instance of [`SinkSource`].
-#[derive(Serialize)]
+#[derive(Serialize, Getters, Setters)]
 pub(crate) struct SinksSourcesGroup {
     #[serde(rename = "@id")]
     id: &'static str,
@@ -246,6 +287,10 @@ pub(crate) struct SinksSourcesGroup {
     clip_path: &'static str,
     #[serde(rename = "@class")]
     class: &'static str,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    label: Option<GroupLabel>,
+    #[getset(get = "pub")]
     g: Vec<SinkSource>,
 }
 
@@ -259,6 +304,7 @@ impl SinksSourcesGroup {
             // Formula worksout because we ignore the corners. Obv, only on 2D matrix.
             g: Vec::with_capacity(usize::try_from((rows + columns) * 2).expect(UNSUPPORTED_PLATFORM)),
             clip_path: USE_FREEFORM_CLIP_PATH,
+            label: None,
         }
     }
 
@@ -280,6 +326,9 @@ impl SinksSourcesGroup {
     /// Utility to insert a [`SinkSource`] in the list and keep track of its offsets.
     fn insert_router(
         &mut self,
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        column: &CoordinateT,
         router_x: &CoordinateT,
         router_y: &CoordinateT,
         direction: &SinkSourceDirection,
@@ -288,6 +337,7 @@ impl SinksSourcesGroup {
         offsets: &mut Offsets,
     ) {
         let router = SinkSource::new(
+            DataAttributes::border_router(core_id, row, column, direction),
             router_x,
             router_y,
             &direction,
@@ -301,6 +351,9 @@ impl SinksSourcesGroup {
     /// Generates and inserts all edge routers ([`SinkSource`]s) connected to the [`Router`] located at the given coordinates.
     pub(crate) fn insert(
         &mut self,
+        core_id: &ElementIDT,
+        row: &CoordinateT,
+        column: &CoordinateT,
         edge_position: &EdgePosition,
         router_x: &CoordinateT,
         router_y: &CoordinateT,
@@ -318,6 +371,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::North;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -330,6 +386,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::North;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -341,6 +400,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::West;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -353,6 +415,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::North;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -364,6 +429,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::East;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -376,6 +444,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::West;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -388,6 +459,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::East;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -400,6 +474,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::South;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -412,6 +489,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::South;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -423,6 +503,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::West;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -435,6 +518,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::South;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -446,6 +532,9 @@ impl SinksSourcesGroup {
                 direction = SinkSourceDirection::East;
                 variant = self.get_variant(core_borders, direction);
                 self.insert_router(
+                    core_id,
+                    row,
+                    column,
                     router_x,
                     router_y,
                     &direction,
@@ -1,20 +1,109 @@
+use std::{borrow::Cow, collections::BTreeSet, fmt};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
 use const_format::concatcp;
 use getset::{Getters, MutGetters};
-use serde::Serialize;
+use serde::{ser::SerializeStruct, Serialize};
+
+use crate::{
+    generation_error, FontEmbedding, SVGError, SerialiseOptions, Theme, LIGHT_COLOURS, SVG,
+};
 
-pub(crate) const DEFAULT_FILL: &str = "#e5e5e5";
+pub(crate) const DEFAULT_FILL: &str = LIGHT_COLOURS.base_fill;
 pub(crate) const BASE_FILL_CLASS_NAME: &'static str = "baseFill";
 pub(crate) const EDGE_DATA_CLASS_NAME: &'static str = "edgeData";
 
-const FONT_FACE: &str = concatcp!(
-    r#"
+/// Roboto Mono, the font every text in the [`SVG`] uses, as base64 encoded TrueType.
+pub(crate) const ROBOTO_MONO_BASE64: &str = include_str!("assets/roboto_mono_base64.txt");
+
+/// Decodes [`ROBOTO_MONO_BASE64`] into TrueType bytes, for subsetting, text measuring and renderers that can't read `@font-face`.
+pub(crate) fn roboto_mono_font() -> Result<Vec<u8>, SVGError> {
+    STANDARD
+        .decode(ROBOTO_MONO_BASE64.trim())
+        .map_err(|error| generation_error(format!("Could not decode Roboto Mono: {error}")))
+}
+
+const FONT_FACE_START: &str = r#"
 @font-face {
     font-family: "Roboto Mono";
-    src: url(data:font/truetype;charset=utf-8;base64,"#,
-    include_str!("assets/roboto_mono_base64.txt"),
-    r#") format("truetype");
-}"#
-);
+    src: url(data:font/truetype;charset=utf-8;base64,"#;
+const FONT_FACE_END: &str = r#") format("truetype");
+}"#;
+const FONT_FACE: &str = concatcp!(FONT_FACE_START, ROBOTO_MONO_BASE64, FONT_FACE_END);
+
+/// Generates an `@font-face` rule embedding only the Roboto Mono glyphs for the provided characters.
+fn subset_font_face(characters: &BTreeSet<char>) -> Result<String, SVGError> {
+    let font = roboto_mono_font()?;
+    let face = ttf_parser::Face::parse(&font, 0)
+        .map_err(|error| generation_error(format!("Could not parse Roboto Mono: {error}")))?;
+
+    // .notdef is always kept, renderers fall back to it.
+    let mut glyphs = vec![0];
+    glyphs.extend(
+        characters
+            .iter()
+            .filter_map(|character| face.glyph_index(*character))
+            .map(|glyph| glyph.0),
+    );
+
+    let subset = subsetter::subset(&font, 0, subsetter::Profile::pdf(&glyphs))
+        .map_err(|error| generation_error(format!("Could not subset Roboto Mono: {error}")))?;
+
+    Ok(format!(
+        "{}{}{}",
+        FONT_FACE_START,
+        STANDARD.encode(subset),
+        FONT_FACE_END
+    ))
+}
+
+/// Escapes a value to be used as a CSS string.
+fn css_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Font family used by [`FontEmbedding::External`], loaded from `url` if provided.
+/// Defaults to Roboto Mono, expected to be installed where the [`SVG`] is displayed.
+pub(crate) struct ExternalFont {
+    family: String,
+    url: Option<String>,
+}
+
+impl Default for ExternalFont {
+    fn default() -> Self {
+        Self {
+            family: String::from("Roboto Mono"),
+            url: None,
+        }
+    }
+}
+
+/// A subset font face and the characters it covers.
+pub(crate) struct FontSubset {
+    characters: BTreeSet<char>,
+    font_face: String,
+}
+
+/// Generates the CSS that makes text use an external font family, loaded from `url` if provided.
+fn external_font_face(ExternalFont { family, url }: &ExternalFont) -> String {
+    let family = css_string(family);
+    let mut font_face = String::new();
+
+    if let Some(url) = url {
+        font_face.push_str(
+            format!(
+                "\n@font-face {{\n    font-family: \"{}\";\n    src: url(\"{}\");\n}}",
+                family,
+                css_string(url)
+            )
+            .as_str(),
+        );
+    }
+    // CSS takes precedence over the font-family attribute of text elements.
+    font_face.push_str(format!("\ntext {{font-family: \"{}\";}}", family).as_str());
+
+    font_face
+}
 
 const BASE_STYLE: &str = concatcp!(
     FONT_FACE,
@@ -27,12 +116,47 @@ const BASE_STYLE: &str = concatcp!(
 
 static DEFAULT_STYLE: &str = concatcp!(BASE_STYLE, "\n.", EDGE_DATA_CLASS_NAME, "{display: none;}");
 
+/// A CSS rule targeting a single element by ID, e.g. `#c0 {fill: #f97316;}`.
+/// Kept structured, rather than as text, so that animations can turn it into `<set>` elements.
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub(crate) struct ElementRule {
+    id: String,
+    declarations: Vec<(&'static str, String)>,
+}
+
+impl ElementRule {
+    /// Generates a new [`ElementRule`] for the element with the provided ID.
+    pub(crate) fn new(id: String, declarations: Vec<(&'static str, String)>) -> Self {
+        Self { id, declarations }
+    }
+}
+
+impl fmt::Display for ElementRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n#{} {{", self.id)?;
+        for (i, (property, value)) in self.declarations.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}: {};", property, value)?;
+        }
+        f.write_str("}")
+    }
+}
+
 /// Object representation of SVG `<style>`.
-#[derive(Serialize, MutGetters, Getters)]
+/// Rules shared by the whole [`SVG`] come first, [`ElementRule`]s follow.
+#[derive(MutGetters, Getters)]
 pub(crate) struct Style {
-    #[serde(rename = "$text")]
-    #[getset(get_mut = "pub", get = "pub")]
     css: String,
+    #[getset(get_mut = "pub", get = "pub")]
+    element_rules: Vec<ElementRule>,
+    /// The font rules the stylesheet starts with.
+    font_face: Cow<'static, str>,
+    /// The [`Theme`] applied to the stylesheet.
+    #[getset(get = "pub")]
+    theme: Theme,
 }
 
 impl Style {
@@ -40,14 +164,173 @@ impl Style {
     pub(crate) fn base() -> Self {
         Self {
             css: BASE_STYLE.into(),
+            element_rules: Vec::new(),
+            font_face: Cow::Borrowed(FONT_FACE),
+            theme: Theme::Light,
+        }
+    }
+
+    /// Generates the whole stylesheet, shared rules followed by [`ElementRule`]s.
+    pub(crate) fn stylesheet(&self) -> String {
+        let mut stylesheet = self.css.clone();
+        for rule in &self.element_rules {
+            stylesheet.push_str(&rule.to_string());
+        }
+
+        stylesheet
+    }
+
+    /// Replaces the font rules the stylesheet starts with.
+    fn set_font_face(&mut self, font_face: String) {
+        if self.css.starts_with(self.font_face.as_ref()) {
+            self.css.replace_range(..self.font_face.len(), &font_face);
+            self.font_face = Cow::Owned(font_face);
         }
     }
 }
 
+impl Style {
+    /// Appends the CSS required by the provided [`Theme`], if any.
+    pub(crate) fn apply_theme(&mut self, theme: &Theme) {
+        if let Some(theme_css) = theme.css() {
+            self.css.push_str(&theme_css);
+        }
+        self.theme = *theme;
+    }
+}
+
 impl Default for Style {
     fn default() -> Self {
         Self {
             css: DEFAULT_STYLE.into(),
+            element_rules: Vec::new(),
+            font_face: Cow::Borrowed(FONT_FACE),
+            theme: Theme::Light,
+        }
+    }
+}
+
+impl Serialize for Style {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut style = serializer.serialize_struct("style", 1)?;
+        style.serialize_field("$text", &self.stylesheet())?;
+        style.end()
+    }
+}
+
+impl SVG {
+    /// Collects every character in the text content of the [`SVG`].
+    fn used_characters(&self) -> Result<BTreeSet<char>, SVGError> {
+        let mut document = String::new();
+        SerialiseOptions::compact().write_fragment(&self.root, Some("g"), &mut document)?;
+
+        let mut characters = BTreeSet::new();
+        // Text content sits between the end of a tag and the start of the next one.
+        for text in document
+            .split('<')
+            .filter_map(|tag| tag.split_once('>').map(|(_, text)| text))
+        {
+            let text = quick_xml::escape::unescape(text).unwrap_or(Cow::Borrowed(text));
+            characters.extend(text.chars());
         }
+
+        Ok(characters)
+    }
+
+    /// Swaps the font rules in the stylesheet according to the [`FontEmbedding`] in use.
+    /// Must run once the [`SVG`] content is final, as subsetting depends on it.
+    pub(crate) fn apply_font_embedding(&mut self) -> Result<(), SVGError> {
+        let font_face = match self.base_configuration.font_embedding() {
+            FontEmbedding::Full => return Ok(()),
+            FontEmbedding::Subset => {
+                let characters = self.used_characters()?;
+
+                // Subsetting is expensive, the font face only changes with the characters in use.
+                match self.font_subset.as_ref() {
+                    Some(subset) if subset.characters == characters => subset.font_face.clone(),
+                    _ => {
+                        let font_face = subset_font_face(&characters)?;
+                        self.font_subset = Some(FontSubset {
+                            characters,
+                            font_face: font_face.clone(),
+                        });
+
+                        font_face
+                    }
+                }
+            }
+            FontEmbedding::External => external_font_face(&self.external_font),
+        };
+
+        self.style.set_font_face(font_face);
+
+        Ok(())
+    }
+
+    /// Sets the font used by [`FontEmbedding::External`], loaded from `url` if provided.
+    /// The font is kept across updates, including those regenerating the whole [`SVG`].
+    pub fn set_external_font(
+        &mut self,
+        family: String,
+        url: Option<String>,
+    ) -> Result<(), SVGError> {
+        self.external_font = ExternalFont { family, url };
+
+        self.apply_font_embedding()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{test_configuration, test_system},
+        BaseConfiguration, Configuration, SVG,
+    };
+
+    #[test]
+    fn can_reuse_font_subset() {
+        let mut manycore = test_system();
+        let base_configuration: BaseConfiguration = serde_json::from_str(
+            r#"{"attribute_font_size": 16.0, "task_font_size": 22.0, "font_embedding": "Subset"}"#,
+        )
+        .expect("Could not parse base configuration");
+
+        let mut svg = SVG::try_from_manycore_with_base_config(&manycore, &base_configuration)
+            .expect("Could not convert Manycore to SVG.");
+        let characters = |svg: &SVG| {
+            svg.font_subset
+                .as_ref()
+                .map(|subset| subset.characters.clone())
+                .expect("Missing font subset")
+        };
+        assert!(!characters(&svg).contains(&'m'));
+
+        // Same text, the cached subset is reused as it is.
+        svg.font_subset
+            .as_mut()
+            .expect("Missing font subset")
+            .font_face = String::from("\n/* cached */");
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut Configuration::default(),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+        assert!(update.style.starts_with("\n/* cached */"));
+
+        // New text, e.g. "Temp", requires a new subset.
+        let update = svg
+            .update_configurable_information(
+                &mut manycore,
+                &mut test_configuration("conf3.json"),
+                &base_configuration,
+            )
+            .expect("Could not generate SVG update");
+        assert!(characters(&svg).contains(&'m'));
+        assert!(update.style.contains("font-family: \"Roboto Mono\";"));
     }
 }
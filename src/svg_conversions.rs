@@ -1,27 +1,21 @@
 /// [`SVG`] conversion utilities.
 use manycore_parser::{ManycoreSystem, SystemDimensionsT, WithID};
 use quick_xml::DeError;
-use serde::Serialize;
 use std::cmp::min;
 
 use crate::{
     tasks_group::{missing_task, TASK_RECT_STROKE},
-    BaseConfiguration, CoordinateT, Offsets, ProcessingGroup, SVGError, TopLeft, ViewBox,
-    BLOCK_DISTANCE, BLOCK_LENGTH, CORE_ROUTER_STROKE_WIDTH, SVG, UNSUPPORTED_PLATFORM,
+    BaseConfiguration, CoordinateT, Offsets, ProcessedBaseConfiguration, ProcessingGroup, SVGError,
+    SerialiseOptions, TopLeft, Topology, ViewBox, CORE_ROUTER_STROKE_WIDTH, SVG,
+    UNSUPPORTED_PLATFORM,
 };
 
 impl TryFrom<&SVG> for String {
     type Error = DeError;
 
+    /// Serialises the [`SVG`] with the default [`SerialiseOptions`].
     fn try_from(svg: &SVG) -> Result<Self, Self::Error> {
-        let mut buf = String::new();
-        let mut serialiser = quick_xml::se::Serializer::new(&mut buf);
-        serialiser.indent(' ', 4);
-        serialiser.set_quote_level(quick_xml::se::QuoteLevel::Minimal);
-
-        svg.serialize(serialiser)?;
-
-        Ok(buf)
+        SerialiseOptions::default().serialise_document(svg)
     }
 }
 
@@ -36,13 +30,17 @@ impl SVG {
         let columns_coord: CoordinateT = columns.into();
         let rows_coord: CoordinateT = rows.into();
 
+        let topology = *base_configuration.topology();
+        let processed_base_configuration = ProcessedBaseConfiguration::from(&base_configuration);
+        let geometry = processed_base_configuration.geometry();
+
         // Each column * each block + the distance between blocks + the stroke
-        let width = (columns_coord * BLOCK_LENGTH)
-            + ((columns_coord - 1) * BLOCK_DISTANCE)
+        let width = (columns_coord * geometry.block_length())
+            + ((columns_coord - 1) * geometry.block_distance())
             + CORE_ROUTER_STROKE_WIDTH.saturating_mul(2);
         // Each row * each block + the distance between blocks + the stroke
-        let height = (rows_coord * BLOCK_LENGTH)
-            + ((rows_coord - 1) * BLOCK_DISTANCE)
+        let height = (rows_coord * geometry.block_length())
+            + ((rows_coord - 1) * geometry.block_distance())
             + CORE_ROUTER_STROKE_WIDTH.saturating_mul(2);
 
         // viewBox top left
@@ -52,7 +50,14 @@ impl SVG {
         };
 
         // The SVG we'll return
-        let mut ret = SVG::new(manycore, width, height, top_left, base_configuration);
+        let mut ret = SVG::new(
+            manycore,
+            width,
+            height,
+            top_left,
+            base_configuration,
+            processed_base_configuration,
+        );
 
         // Row tracker for iteration
         let mut r: SystemDimensionsT = 0;
@@ -87,6 +92,7 @@ impl SVG {
                 core.id(),
                 &ret.top_left,
                 ret.defs.clip_paths_mut(),
+                ret.processed_base_configuration.geometry(),
             )?;
 
             // Add task
@@ -100,6 +106,7 @@ impl SVG {
                 let task = ret.root.tasks_group.add_task(
                     &r_coord,
                     &c_coord,
+                    core.id(),
                     allocated_task,
                     &top_left,
                     &ret.processed_base_configuration,
@@ -122,16 +129,26 @@ impl SVG {
             }
 
             // Generate connections group
-            ret.root
-                .connections_group
-                .add_connections(core, &r_coord, &c_coord, &ret.top_left);
+            ret.root.connections_group.add_connections(
+                core,
+                &r_coord,
+                &c_coord,
+                &rows_coord,
+                &columns_coord,
+                &ret.top_left,
+                &topology,
+                &ret.processed_base_configuration,
+            );
 
-            // Generate borders
-            if let Some(edge_position) = core.matrix_edge() {
+            // Generate borders. A torus has no border routers, edge links wrap around instead.
+            if let (Topology::Mesh, Some(edge_position)) = (topology, core.matrix_edge()) {
                 let (router_x, router_y) = processing_group.router().move_coordinates();
 
                 // Remember that index always corresponts to core ID (collections is sorted when converting manycore into SVG).
                 ret.root.sinks_sources_group.insert(
+                    core.id(),
+                    &r_coord,
+                    &c_coord,
                     edge_position,
                     router_x,
                     router_y,
@@ -165,9 +182,31 @@ impl SVG {
             );
         }
 
-        // Calculate borders viewBox.
-        let borders_view_box = ViewBox::from(&borders_offsets);
-        ret.borders_view_box = borders_view_box;
+        match topology {
+            Topology::Mesh => {
+                // Calculate borders viewBox.
+                let borders_view_box = ViewBox::from(&borders_offsets);
+                ret.borders_view_box = borders_view_box;
+            }
+            Topology::Torus => {
+                // Wrap-around connections are routed around the matrix, the base viewBox must fit them.
+                let wrap_offsets = *ret.root.connections_group.wrap_offsets();
+                ret.fit_base_view_box(&wrap_offsets);
+
+                // There are no borders to show.
+                ret.borders_view_box = ret.base_view_box;
+            }
+        }
+
+        if *ret.base_configuration.tooltips() {
+            ret.add_tooltips(manycore)?;
+        }
+
+        if *ret.base_configuration.accessibility() {
+            ret.add_accessibility(manycore);
+        }
+
+        ret.apply_font_embedding()?;
 
         Ok(ret)
     }
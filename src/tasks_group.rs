@@ -1,29 +1,34 @@
 use std::{
     cmp::max,
     collections::{btree_map::Values, BTreeMap},
+    fmt,
     ops::{Add, Sub},
 };
 
-use getset::Getters;
+use const_format::concatcp;
+use getset::{Getters, Setters};
 use manycore_parser::ElementIDT;
 use manycore_utils::serialise_btreemap;
 use quick_xml::DeError;
 use serde::Serialize;
 
 use crate::{
-    generation_error, partial_update::PartialUpdate, CoordinateT, FontSizeT,
-    ProcessedBaseConfiguration, ProcessingGroup, SVGError, SVGErrorKind, TaskRectConfiguration,
-    TextInformation, TopLeft, BLOCK_DISTANCE, BLOCK_LENGTH, CHAR_H_PADDING,
-    CORE_ROUTER_STROKE_WIDTH_STR, HALF_CHAR_V_PADDING, ROUTER_OFFSET, SIDE_LENGTH,
-    USE_FREEFORM_CLIP_PATH,
+    generation_error, partial_update::PartialUpdate, CoordinateT, DataAttributes, FontSizeT,
+    GeometryConfiguration, ProcessedBaseConfiguration, ProcessingGroup, SVGError, SVGErrorKind,
+    SerialiseOptions, TaskRectConfiguration, TextInformation, Tooltip, TopLeft, CHAR_H_PADDING,
+    CORE_ROUTER_STROKE_WIDTH_STR, HALF_CHAR_V_PADDING, LIGHT_COLOURS, STROKE_CLASS_NAME,
+    TASK_CLASS_NAME, USE_FREEFORM_CLIP_PATH,
 };
 
 pub(crate) const DEFAULT_TASK_FONT_SIZE: FontSizeT = 22.0;
 pub(crate) static MINIMUM_TASK_FONT_SIZE: FontSizeT = 16.0;
 pub(crate) static MAXIMUM_TASK_FONT_SIZE: FontSizeT = 32.0;
 pub(crate) static TASK_RECT_STROKE: CoordinateT = 1;
-static TASK_RECT_FILL: &'static str = "#bfdbfe";
+static TASK_RECT_FILL: &'static str = LIGHT_COLOURS.task_fill;
+static TASK_RECT_CLASS_NAME: &'static str = concatcp!(TASK_CLASS_NAME, " ", STROKE_CLASS_NAME);
 static TASK_RECT_X_OFFSET: CoordinateT = 10;
+static CHANGED_TASK_RECT_STROKE: &'static str = "#f59e0b";
+static CHANGED_TASK_RECT_STROKE_WIDTH: &'static str = "4";
 
 /// Object representation of the SVG `<rect>` that wraps a task id.
 #[derive(Serialize, Getters)]
@@ -42,6 +47,8 @@ pub(crate) struct TaskRect {
     height: CoordinateT,
     #[serde(rename = "@rx")]
     rx: &'static str,
+    #[serde(rename = "@class")]
+    class: &'static str,
     #[serde(rename = "@fill")]
     fill: &'static str,
     #[serde(rename = "@stroke")]
@@ -64,12 +71,21 @@ impl TaskRect {
             width: text_width,
             height: *task_rect_configuration.task_rect_height(),
             rx: "10",
+            class: TASK_RECT_CLASS_NAME,
             fill: TASK_RECT_FILL,
-            stroke: "black",
+            stroke: LIGHT_COLOURS.stroke,
             stroke_width: CORE_ROUTER_STROKE_WIDTH_STR,
         }
     }
 
+    /// Marks a [`TaskRect`] whose allocation changed, with a thicker, highlighted border.
+    fn mark_changed(&mut self) {
+        // The highlighted border is not themed.
+        self.class = TASK_CLASS_NAME;
+        self.stroke = CHANGED_TASK_RECT_STROKE;
+        self.stroke_width = CHANGED_TASK_RECT_STROKE_WIDTH;
+    }
+
     /// Toggles [`TaskRect`] between cost inclusive and base variant depending on provided config.
     fn toggle_variant(
         &mut self,
@@ -92,8 +108,10 @@ impl TaskRect {
 struct BaseVariant(bool);
 
 /// Helper struct to group [`TaskRect`] and its corresponding [`TextInformation`] together, forms the task bubble in the SVG.
-#[derive(Serialize, Getters)]
+#[derive(Serialize, Getters, Setters)]
 pub(crate) struct Task {
+    #[serde(flatten)]
+    data: DataAttributes,
     #[getset(get = "pub")]
     rect: TaskRect,
     #[serde(rename = "text")]
@@ -106,6 +124,9 @@ pub(crate) struct Task {
     task_width: CoordinateT,
     #[serde(skip)]
     cost_width: CoordinateT,
+    #[serde(flatten)]
+    #[getset(set = "pub(crate)")]
+    tooltip: Option<Tooltip>,
 }
 
 impl Task {
@@ -116,15 +137,17 @@ impl Task {
         text_width: CoordinateT,
         top_left: &TopLeft,
         task_rect_configuration: &TaskRectConfiguration,
+        geometry: &GeometryConfiguration,
     ) -> (CoordinateT, CoordinateT) {
-        let cx = column * BLOCK_LENGTH + column * BLOCK_DISTANCE - (text_width.saturating_div(2))
+        let cx = column * geometry.block_length() + column * geometry.block_distance()
+            - (text_width.saturating_div(2))
             + TASK_RECT_X_OFFSET
             + top_left.x();
         let cy = task_rect_configuration
             .task_rect_centre_offset()
-            .add(row.saturating_mul(BLOCK_LENGTH.add(BLOCK_DISTANCE)))
-            .add(ROUTER_OFFSET)
-            .add(SIDE_LENGTH)
+            .add(row.saturating_mul(geometry.block_length().add(geometry.block_distance())))
+            .add(geometry.router_offset())
+            .add(geometry.side_length())
             .add(top_left.y);
 
         (cx, cy)
@@ -134,6 +157,7 @@ impl Task {
     pub(crate) fn new(
         row: &CoordinateT,
         column: &CoordinateT,
+        core_id: &ElementIDT,
         task: &manycore_parser::Task,
         top_left: &TopLeft,
         processed_base_configuration: &ProcessedBaseConfiguration,
@@ -141,17 +165,17 @@ impl Task {
         let task_text = format!("T{}", task.id());
         let cost_text = format!("[{}]", task.computation_cost());
 
-        // Get an approx text width
+        // Measure text width
         let task_text_width = TextInformation::calculate_length_util(
             *processed_base_configuration.task_font_size(),
-            task_text.len(),
+            &task_text,
             Some(CHAR_H_PADDING),
-        )?;
+        );
         let cost_text_width = TextInformation::calculate_length_util(
             *processed_base_configuration.task_font_size(),
-            cost_text.len(),
+            &cost_text,
             Some(CHAR_H_PADDING),
-        )?;
+        );
 
         // Get centre coordinates
         let (cx, cy) = Self::get_centre_coordinates(
@@ -160,9 +184,11 @@ impl Task {
             task_text_width,
             top_left,
             processed_base_configuration.task_rect(),
+            processed_base_configuration.geometry(),
         );
 
         Ok(Self {
+            data: DataAttributes::task(task.id(), core_id, row, column),
             rect: TaskRect::new(
                 cx,
                 cy,
@@ -187,6 +213,7 @@ impl Task {
             cost_string: cost_text,
             task_width: task_text_width,
             cost_width: cost_text_width,
+            tooltip: None,
         })
     }
 
@@ -207,6 +234,7 @@ impl Task {
             text_width,
             top_left,
             processed_base_configuration.task_rect(),
+            processed_base_configuration.geometry(),
         );
 
         self.rect.toggle_variant(
@@ -251,6 +279,7 @@ impl Task {
             self.task_width,
             top_left,
             processed_base_configuration.task_rect(),
+            processed_base_configuration.geometry(),
         );
 
         self.rect.toggle_variant(
@@ -266,10 +295,11 @@ impl Task {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Setters)]
 pub(crate) struct TasksGroup {
     #[serde(rename = "@id")]
-    id: &'static str,
+    #[getset(set = "pub")]
+    id: String,
     #[serde(rename = "g", serialize_with = "serialise_btreemap")]
     tasks: BTreeMap<u16, Task>,
     #[serde(skip)]
@@ -282,7 +312,7 @@ impl TasksGroup {
     /// Creates a new [`TasksGroup`] instance with enough capacity for the provided number of tasks.
     pub(crate) fn new() -> Self {
         Self {
-            id: "tasks",
+            id: String::from("tasks"),
             tasks: BTreeMap::new(),
             variant: BaseVariant(true),
             clip_path: USE_FREEFORM_CLIP_PATH,
@@ -294,15 +324,28 @@ impl TasksGroup {
         &mut self,
         row: &CoordinateT,
         column: &CoordinateT,
+        core_id: &ElementIDT,
         task: &manycore_parser::Task,
         top_left: &TopLeft,
         processed_base_configuration: &ProcessedBaseConfiguration,
     ) -> Result<&Task, SVGError> {
-        let task_g = Task::new(row, column, task, top_left, processed_base_configuration)?;
+        let task_g = Task::new(
+            row,
+            column,
+            core_id,
+            task,
+            top_left,
+            processed_base_configuration,
+        )?;
 
         Ok(self.tasks.entry(*task.id()).or_insert(task_g))
     }
 
+    /// Retrieves the requested [`Task`], if present.
+    pub(crate) fn get_task_mut(&mut self, task_id: &u16) -> Option<&mut Task> {
+        self.tasks.get_mut(task_id)
+    }
+
     /// Returns whether the [`TasksGroup`] is in the base variant.
     pub(crate) fn is_base(&self) -> bool {
         self.variant.0
@@ -313,6 +356,19 @@ impl TasksGroup {
         self.variant.0 = !self.variant.0;
     }
 
+    /// Marks the requested task as allocated to a different core than in a reference system.
+    pub(crate) fn mark_changed(&mut self, task_id: &u16) -> Result<(), SVGError> {
+        self.tasks
+            .get_mut(task_id)
+            .ok_or_else(|| {
+                generation_error(format!("Could not find Task {task_id} in TasksGroup."))
+            })?
+            .rect
+            .mark_changed();
+
+        Ok(())
+    }
+
     /// Toggles the requested task.
     pub(crate) fn toggle_task(
         &mut self,
@@ -338,11 +394,14 @@ impl TasksGroup {
 }
 
 impl PartialUpdate for TasksGroup {
-    fn update_string(&self) -> Result<String, DeError> {
+    fn write_update<W: fmt::Write>(
+        &self,
+        writer: W,
+        options: &SerialiseOptions,
+    ) -> Result<(), DeError> {
         let tasks: Vec<&Task> = self.tasks.values().collect();
-        let tasks = quick_xml::se::to_string_with_root("g", &tasks)?;
 
-        Ok(tasks)
+        options.write_fragment(&tasks, Some("g"), writer)
     }
 }
 
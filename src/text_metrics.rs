@@ -0,0 +1,85 @@
+use std::sync::OnceLock;
+
+use ttf_parser::Face;
+
+use crate::{style::roboto_mono_font, FontSizeT};
+
+/// Advance of a Roboto Mono character relative to the font size, used if the embedded font can't be read.
+static FALLBACK_ADVANCE: FontSizeT = 1.0 / 1.665;
+
+/// Decoded Roboto Mono, kept for the lifetime of the program so that [`FACE`] can borrow it.
+static FONT: OnceLock<Option<Vec<u8>>> = OnceLock::new();
+/// Parsed Roboto Mono, so the font is only decoded and parsed once.
+static FACE: OnceLock<Option<Face<'static>>> = OnceLock::new();
+
+/// Returns the parsed embedded font, if it could be read.
+fn face() -> Option<&'static Face<'static>> {
+    FACE.get_or_init(|| {
+        FONT.get_or_init(|| roboto_mono_font().ok())
+            .as_deref()
+            .and_then(|font| Face::parse(font, 0).ok())
+    })
+    .as_ref()
+}
+
+/// Whether a character is usually rendered a full em wide (East Asian wide and fullwidth ranges).
+/// Roboto Mono has no glyphs for these, renderers fall back to a font that does.
+fn is_wide(character: char) -> bool {
+    matches!(
+        character,
+        '\u{1100}'..='\u{115F}'
+            | '\u{2E80}'..='\u{303E}'
+            | '\u{3041}'..='\u{33FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{A000}'..='\u{A4CF}'
+            | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}'
+            | '\u{1F300}'..='\u{1F64F}'
+            | '\u{1F900}'..='\u{1F9FF}'
+            | '\u{20000}'..='\u{3FFFD}'
+    )
+}
+
+/// Estimated advance of `character` relative to the font size, for characters Roboto Mono can't measure.
+/// Renderers fall back to another font for those, assume it has the same proportions.
+fn estimated_advance(character: char) -> FontSizeT {
+    if is_wide(character) {
+        1.0
+    } else if character.is_control() {
+        0.0
+    } else {
+        FALLBACK_ADVANCE
+    }
+}
+
+/// Width in pixels of `text` set in Roboto Mono at `font_size`, from the font's advance widths.
+pub(crate) fn text_width(text: &str, font_size: FontSizeT) -> FontSizeT {
+    let relative_width: FontSizeT = match face() {
+        Some(face) => {
+            let units_per_em = FontSizeT::from(face.units_per_em());
+
+            text.chars()
+                .map(|character| {
+                    face.glyph_index(character)
+                        .and_then(|glyph| face.glyph_hor_advance(glyph))
+                        .map_or_else(
+                            || estimated_advance(character),
+                            |advance| FontSizeT::from(advance) / units_per_em,
+                        )
+                })
+                .sum()
+        }
+        None => text.chars().map(estimated_advance).sum(),
+    };
+
+    relative_width * font_size
+}
+
+/// Width in pixels of a single Roboto Mono character at `font_size`. Used to pad text.
+pub(crate) fn char_width(font_size: FontSizeT) -> FontSizeT {
+    text_width(" ", font_size)
+}
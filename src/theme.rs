@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use crate::style::BASE_FILL_CLASS_NAME;
+
+#[cfg(any(feature = "png", feature = "pdf"))]
+use crate::{export_error, SVGError};
+
+#[cfg(any(doc, feature = "png", feature = "pdf"))]
+use crate::SVG;
+
+#[cfg(doc)]
+use crate::Configuration;
+
+// Classes elements with themed colours are generated with.
+// Elements with user requested colours don't get them, so themes never override those colours.
+pub(crate) const STROKE_CLASS_NAME: &'static str = "themeStroke";
+pub(crate) const TEXT_CLASS_NAME: &'static str = "themeText";
+pub(crate) const TASK_CLASS_NAME: &'static str = "themeTask";
+pub(crate) const SINK_CLASS_NAME: &'static str = "themeSink";
+pub(crate) const SOURCE_CLASS_NAME: &'static str = "themeSource";
+pub(crate) const MARKER_CLASS_NAME: &'static str = "themeMarker";
+
+/// Colours a [`Theme`] applies to the [`SVG`].
+pub(crate) struct ThemeColours {
+    pub(crate) background: &'static str,
+    pub(crate) base_fill: &'static str,
+    pub(crate) stroke: &'static str,
+    pub(crate) text: &'static str,
+    pub(crate) task_fill: &'static str,
+    pub(crate) sink_fill: &'static str,
+    pub(crate) source_fill: &'static str,
+    pub(crate) marker_fill: &'static str,
+}
+
+impl ThemeColours {
+    /// The CSS variables the colours are exposed as, with their colour.
+    fn variables(&self) -> [(&'static str, &'static str); 8] {
+        [
+            ("--background", self.background),
+            ("--base-fill", self.base_fill),
+            ("--stroke", self.stroke),
+            ("--text", self.text),
+            ("--task-fill", self.task_fill),
+            ("--sink-fill", self.sink_fill),
+            ("--source-fill", self.source_fill),
+            ("--marker-fill", self.marker_fill),
+        ]
+    }
+}
+
+/// Light theme colours. These are the colours elements are generated with.
+pub(crate) const LIGHT_COLOURS: ThemeColours = ThemeColours {
+    background: "white",
+    base_fill: "#e5e5e5",
+    stroke: "black",
+    text: "black",
+    task_fill: "#bfdbfe",
+    sink_fill: "#fb923c",
+    source_fill: "#fbbf24",
+    marker_fill: "black",
+};
+
+static DARK_COLOURS: ThemeColours = ThemeColours {
+    background: "#171717",
+    base_fill: "#404040",
+    stroke: "#d4d4d4",
+    text: "#f5f5f5",
+    task_fill: "#1e3a8a",
+    sink_fill: "#c2410c",
+    source_fill: "#a16207",
+    marker_fill: "#d4d4d4",
+};
+
+static HIGH_CONTRAST_COLOURS: ThemeColours = ThemeColours {
+    background: "black",
+    base_fill: "black",
+    stroke: "white",
+    text: "white",
+    task_fill: "#0000cd",
+    sink_fill: "#8b0000",
+    source_fill: "#006400",
+    marker_fill: "white",
+};
+
+/// Colour themes that can be applied to an [`SVG`] through its [`Configuration`].
+/// Themes only affect the SVG `<style>`, so switching theme never requires a full re-generation.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Default)]
+pub enum Theme {
+    /// Default theme.
+    #[default]
+    Light,
+    /// Light elements on a dark background.
+    Dark,
+    /// White strokes and text on black, with saturated fills, for low-vision readers.
+    HighContrast,
+}
+
+impl Theme {
+    /// Returns the [`ThemeColours`] for this [`Theme`].
+    fn colours(&self) -> &'static ThemeColours {
+        match self {
+            Theme::Light => &LIGHT_COLOURS,
+            Theme::Dark => &DARK_COLOURS,
+            Theme::HighContrast => &HIGH_CONTRAST_COLOURS,
+        }
+    }
+
+    /// Generates the CSS required to apply this [`Theme`], if any.
+    ///
+    /// Colours are exposed as CSS variables and applied through the theme classes elements are generated with.
+    /// Elements with user-requested fills and coloured text don't have those classes and keep their colour.
+    /// The light theme matches the generated colours already and requires no CSS.
+    pub(crate) fn css(&self) -> Option<String> {
+        if let Theme::Light = self {
+            return None;
+        }
+
+        let variables = self
+            .colours()
+            .variables()
+            .map(|(variable, colour)| format!("{}: {};", variable, colour))
+            .join(" ");
+
+        Some(format!(
+            "\nsvg {{{} background-color: var(--background);}}\
+            \n.{} {{fill: var(--base-fill);}}\
+            \n.{} {{stroke: var(--stroke);}}\
+            \n.{} {{fill: var(--text);}}\
+            \nline.{} {{stroke: var(--text);}}\
+            \n.{} {{fill: var(--marker-fill);}}\
+            \n.{} {{fill: var(--task-fill);}}\
+            \n.{} {{fill: var(--sink-fill);}}\
+            \n.{} {{fill: var(--source-fill);}}",
+            variables,
+            BASE_FILL_CLASS_NAME,
+            STROKE_CLASS_NAME,
+            TEXT_CLASS_NAME,
+            TEXT_CLASS_NAME,
+            MARKER_CLASS_NAME,
+            TASK_CLASS_NAME,
+            SINK_CLASS_NAME,
+            SOURCE_CLASS_NAME,
+        ))
+    }
+}
+
+#[cfg(any(feature = "png", feature = "pdf"))]
+impl SVG {
+    /// Serialises the [`SVG`] for PNG and PDF exports, which support neither CSS variables
+    /// nor `background-color`: theme colours are written out and the background is drawn as a `<rect>`.
+    pub(crate) fn export_document(&self) -> Result<String, SVGError> {
+        let mut document = String::try_from(self)?;
+
+        let theme = self.style.theme();
+        if let Theme::Light = theme {
+            return Ok(document);
+        }
+
+        let colours = theme.colours();
+        for (variable, colour) in colours.variables() {
+            document = document.replace(&format!("var({})", variable), colour);
+        }
+
+        // First in the <svg>, so that everything is drawn on top of it.
+        let svg_content_start = document
+            .find("<svg")
+            .and_then(|svg_start| {
+                document[svg_start..]
+                    .find('>')
+                    .map(|tag_end| svg_start + tag_end + 1)
+            })
+            .ok_or_else(|| export_error(String::from("Could not find the <svg> element.")))?;
+        let view_box = self.view_box();
+        document.insert_str(
+            svg_content_start,
+            &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                view_box.x, view_box.y, view_box.width, view_box.height, colours.background
+            ),
+        );
+
+        Ok(document)
+    }
+}
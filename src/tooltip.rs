@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use manycore_parser::{ManycoreSystem, WithID, WithXMLAttributes};
+use serde::Serialize;
+
+use crate::{missing_task, CoordinateT, DirectionType, SVGError, SVG};
+
+/// Object representation of the SVG `<title>` and `<desc>` children of an element.
+/// Browsers show the title on hover, the description is there for assistive technologies.
+#[derive(Serialize, Debug)]
+pub(crate) struct Tooltip {
+    title: String,
+    desc: String,
+}
+
+impl Tooltip {
+    /// Generates a new [`Tooltip`] from a heading and the element details, one per line.
+    fn new(heading: String, details: Vec<String>) -> Self {
+        let mut title = heading;
+        for detail in details.iter() {
+            title.push('\n');
+            title.push_str(detail);
+        }
+
+        Self {
+            title,
+            desc: details.join("; "),
+        }
+    }
+}
+
+/// Formats the position of an element in the matrix.
+fn position(r: &CoordinateT, c: &CoordinateT) -> String {
+    format!("Row {}, column {}", r, c)
+}
+
+/// Formats every attribute of an element, without the XML attribute prefix.
+fn attributes_details(attributes: &Option<BTreeMap<String, String>>) -> Vec<String> {
+    attributes
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}: {}", key.trim_start_matches('@'), value))
+        .collect()
+}
+
+impl SVG {
+    /// Adds a [`Tooltip`] to every core, router, connection and task in the [`SVG`].
+    pub(crate) fn add_tooltips(&mut self, manycore: &ManycoreSystem) -> Result<(), SVGError> {
+        for (core, processing_group) in manycore
+            .cores()
+            .list()
+            .iter()
+            .zip(self.root.processing_group.g_mut().iter_mut())
+        {
+            let (r, c) = *processing_group.coordinates();
+
+            // Core
+            let mut details = vec![position(&r, &c)];
+            details.extend(attributes_details(core.other_attributes()));
+            processing_group
+                .core_mut()
+                .set_tooltip(Some(Tooltip::new(format!("Core {}", core.id()), details)));
+
+            // Router
+            let mut details = vec![position(&r, &c)];
+            details.extend(attributes_details(core.router().other_attributes()));
+            processing_group.router_mut().set_tooltip(Some(Tooltip::new(
+                format!("Router {}", core.router().id()),
+                details,
+            )));
+
+            // Channels
+            for (direction, channel) in core.channels().channel() {
+                let mut details = vec![
+                    position(&r, &c),
+                    format!("Load: {}/{}", channel.current_load(), channel.bandwidth()),
+                ];
+                details.extend(attributes_details(channel.other_attributes()));
+
+                if let Some(connection) = self
+                    .root
+                    .connections_group
+                    .get_connection_mut(core.id(), &DirectionType::Out(*direction))
+                {
+                    connection.set_tooltip(Some(Tooltip::new(
+                        format!("{} channel of Core {}", direction, core.id()),
+                        details,
+                    )));
+                }
+
+                // Edge cores also receive data from their border router.
+                if let Some(connection) = self
+                    .root
+                    .connections_group
+                    .get_connection_mut(core.id(), &DirectionType::Source(*direction))
+                {
+                    let mut details = vec![position(&r, &c)];
+                    if let Some(load) = core
+                        .source_loads()
+                        .as_ref()
+                        .and_then(|source_loads| source_loads.get(direction))
+                    {
+                        details.push(format!("Load: {}/{}", load, channel.bandwidth()));
+                    }
+
+                    connection.set_tooltip(Some(Tooltip::new(
+                        format!("{} source of Core {}", direction, core.id()),
+                        details,
+                    )));
+                }
+            }
+        }
+
+        self.add_task_tooltips(manycore)
+    }
+
+    /// Adds a [`Tooltip`] to every task in the [`SVG`], allocated as in `manycore`.
+    pub(crate) fn add_task_tooltips(&mut self, manycore: &ManycoreSystem) -> Result<(), SVGError> {
+        for (core, processing_group) in manycore
+            .cores()
+            .list()
+            .iter()
+            .zip(self.root.processing_group.g().iter())
+        {
+            if let Some(task_id) = core.allocated_task() {
+                let task = manycore
+                    .task_graph()
+                    .tasks()
+                    .get(task_id)
+                    .ok_or_else(|| missing_task(core.id(), task_id))?;
+
+                if let Some(task_group) = self.root.tasks_group.get_task_mut(task_id) {
+                    let (r, c) = processing_group.coordinates();
+
+                    task_group.set_tooltip(Some(Tooltip::new(
+                        format!("Task {}", task.id()),
+                        vec![
+                            format!("Computation cost: {}", task.computation_cost()),
+                            format!("Core {}", core.id()),
+                            position(r, c),
+                        ],
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}